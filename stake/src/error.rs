@@ -2,6 +2,7 @@ use casper_types::ApiError;
 
 #[repr(u16)]
 #[derive(Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     FatalError = 0,
     AdminError = 1,
@@ -18,6 +19,42 @@ pub enum Error {
     StakeAmountIsZero = 12,
     InsufficientStakeBalance = 13,
     RefundTimeError = 14,
+    InsufficientRewardFunds = 15,
+    InvalidApr = 16,
+    InvalidLockPeriod = 17,
+    RewardPeriodEnded = 18,
+    RewardIsZero = 19,
+    InvalidMaxCap = 20,
+    InvalidStakeLimits = 21,
+    InvalidDepositWindow = 22,
+    InvalidAprRange = 23,
+    Overflow = 24,
+    Underflow = 25,
+    ReentrantCall = 26,
+    TransferAmountMismatch = 27,
+    InsufficientAllowance = 28,
+    InvalidTokenVersion = 29,
+    NotOperator = 30,
+    TransfersDisabled = 31,
+    SelfTransfer = 32,
+    PositionIsTokenized = 33,
+    ConflictingPositionTokens = 34,
+    NotPositionOwner = 35,
+    InvalidVestingSchedule = 36,
+    ConflictingRewardSchedules = 37,
+    UnbondingRequired = 38,
+    NoPendingUnstake = 39,
+    StillUnbonding = 40,
+    NotWhitelisted = 41,
+    InvalidMerkleProof = 42,
+    InvalidVoucher = 43,
+    VoucherExpired = 44,
+    VoucherAlreadyUsed = 45,
+    InvalidReferrer = 46,
+    InvalidReferralBps = 47,
+    InvalidFeeBps = 48,
+    FeeIncreaseNotAllowed = 49,
+    UnbondingDisabled = 50,
     // RewardRateError = 2,
     // RewardDurationError = 3,
     // StakeAmountError = 4,
@@ -0,0 +1,96 @@
+// ref => https://github.com/casper-ecosystem/casper-nft-cep47/blob/master/cep47/src/cep47.rs
+use crate::utils::{encode_hex, get_current_address};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+};
+use casper_contract::contract_api::storage;
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{bytesrepr::ToBytes, Key, PublicKey, URef, U256};
+
+pub enum StakeEvent {
+    MaxCapUpdated { max_cap: U256 },
+    StakeLimitsUpdated { min_stake: U256, max_stake: U256 },
+    DepositWindowExtended { deposit_end_time: u64 },
+    OperatorApproved { staker: Key, operator: Key },
+    OperatorRevoked { staker: Key, operator: Key },
+    PositionTransferred { from: Key, to: Key, amount: U256 },
+    MerkleRootUpdated { merkle_root: Option<[u8; 32]> },
+    VoucherSignerUpdated { voucher_signer: Option<PublicKey> },
+    FeeBpsUpdated { fee_bps: u64 },
+}
+
+pub fn emit(event: &StakeEvent) {
+    let package: Key = get_current_address().into();
+    let mut param: BTreeMap<String, String> = BTreeMap::new();
+    param.insert(
+        "contract_package_hash".to_string(),
+        package.to_formatted_string(),
+    );
+
+    match event {
+        StakeEvent::MaxCapUpdated { max_cap } => {
+            param.insert("event_type".to_string(), "max_cap_updated".to_string());
+            param.insert("max_cap".to_string(), max_cap.to_string());
+        }
+        StakeEvent::StakeLimitsUpdated {
+            min_stake,
+            max_stake,
+        } => {
+            param.insert("event_type".to_string(), "stake_limits_updated".to_string());
+            param.insert("min_stake".to_string(), min_stake.to_string());
+            param.insert("max_stake".to_string(), max_stake.to_string());
+        }
+        StakeEvent::DepositWindowExtended { deposit_end_time } => {
+            param.insert(
+                "event_type".to_string(),
+                "deposit_window_extended".to_string(),
+            );
+            param.insert("deposit_end_time".to_string(), deposit_end_time.to_string());
+        }
+        StakeEvent::OperatorApproved { staker, operator } => {
+            param.insert("event_type".to_string(), "operator_approved".to_string());
+            param.insert("staker".to_string(), staker.to_formatted_string());
+            param.insert("operator".to_string(), operator.to_formatted_string());
+        }
+        StakeEvent::OperatorRevoked { staker, operator } => {
+            param.insert("event_type".to_string(), "operator_revoked".to_string());
+            param.insert("staker".to_string(), staker.to_formatted_string());
+            param.insert("operator".to_string(), operator.to_formatted_string());
+        }
+        StakeEvent::PositionTransferred { from, to, amount } => {
+            param.insert("event_type".to_string(), "position_transferred".to_string());
+            param.insert("from".to_string(), from.to_formatted_string());
+            param.insert("to".to_string(), to.to_formatted_string());
+            param.insert("amount".to_string(), amount.to_string());
+        }
+        StakeEvent::MerkleRootUpdated { merkle_root } => {
+            param.insert("event_type".to_string(), "merkle_root_updated".to_string());
+            param.insert(
+                "merkle_root".to_string(),
+                merkle_root
+                    .map(|merkle_root| encode_hex(&merkle_root))
+                    .unwrap_or_default(),
+            );
+        }
+        StakeEvent::VoucherSignerUpdated { voucher_signer } => {
+            param.insert(
+                "event_type".to_string(),
+                "voucher_signer_updated".to_string(),
+            );
+            param.insert(
+                "voucher_signer".to_string(),
+                voucher_signer
+                    .as_ref()
+                    .map(|voucher_signer| encode_hex(&voucher_signer.to_bytes().unwrap_or_revert()))
+                    .unwrap_or_default(),
+            );
+        }
+        StakeEvent::FeeBpsUpdated { fee_bps } => {
+            param.insert("event_type".to_string(), "fee_bps_updated".to_string());
+            param.insert("fee_bps".to_string(), fee_bps.to_string());
+        }
+    }
+
+    let _: URef = storage::new_uref(param);
+}
@@ -1,19 +1,117 @@
 #![allow(dead_code)]
 extern crate alloc;
 
+use alloc::string::String;
 use casper_contract::contract_api::runtime;
-use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, U256};
+use casper_types::{runtime_args, ApiError, ContractHash, Key, RuntimeArgs, U256};
+use core::convert::TryFrom;
+
+/// Generation of the CEP-18 token contract, which decides the argument names
+/// some entry points expect.
+#[repr(u8)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cep18Version {
+    /// Tokens built from the original ERC-20 port, `balance_of` takes `address`.
+    V1 = 1,
+    /// Tokens that renamed the `balance_of` argument to `owner`.
+    V2 = 2,
+}
+
+impl TryFrom<u8> for Cep18Version {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Cep18Version::V1),
+            2 => Ok(Cep18Version::V2),
+            _ => Err(()),
+        }
+    }
+}
+
+// ref => https://github.com/casper-ecosystem/cep18/blob/dev/cep18/src/error.rs
+/// User errors a CEP-18 token reverts with, so a failed deploy's `User(code)`
+/// can be traced back to the token rather than to this contract.
+#[repr(u16)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cep18Error {
+    InvalidContext = 60000,
+    InsufficientBalance = 60001,
+    InsufficientAllowance = 60002,
+    Overflow = 60003,
+    PackageHashMissing = 60004,
+    PackageHashNotPackage = 60005,
+    InvalidEventsMode = 60006,
+    MissingEventsMode = 60007,
+    Phantom = 60008,
+    FailedToGetArgBytes = 60009,
+    InsufficientRights = 60010,
+    InvalidAdminList = 60011,
+    InvalidMinterList = 60012,
+    InvalidNoneList = 60013,
+    InvalidEnableMBFlag = 60014,
+    AlreadyInitialized = 60015,
+    MintBurnDisabled = 60016,
+    CannotTargetSelfUser = 60017,
+    InvalidBurnTarget = 60018,
+}
+
+impl TryFrom<u16> for Cep18Error {
+    type Error = ();
+
+    fn try_from(code: u16) -> Result<Self, Self::Error> {
+        let error = match code {
+            60000 => Cep18Error::InvalidContext,
+            60001 => Cep18Error::InsufficientBalance,
+            60002 => Cep18Error::InsufficientAllowance,
+            60003 => Cep18Error::Overflow,
+            60004 => Cep18Error::PackageHashMissing,
+            60005 => Cep18Error::PackageHashNotPackage,
+            60006 => Cep18Error::InvalidEventsMode,
+            60007 => Cep18Error::MissingEventsMode,
+            60008 => Cep18Error::Phantom,
+            60009 => Cep18Error::FailedToGetArgBytes,
+            60010 => Cep18Error::InsufficientRights,
+            60011 => Cep18Error::InvalidAdminList,
+            60012 => Cep18Error::InvalidMinterList,
+            60013 => Cep18Error::InvalidNoneList,
+            60014 => Cep18Error::InvalidEnableMBFlag,
+            60015 => Cep18Error::AlreadyInitialized,
+            60016 => Cep18Error::MintBurnDisabled,
+            60017 => Cep18Error::CannotTargetSelfUser,
+            60018 => Cep18Error::InvalidBurnTarget,
+            _ => return Err(()),
+        };
+
+        Ok(error)
+    }
+}
+
+impl TryFrom<ApiError> for Cep18Error {
+    type Error = ();
+
+    fn try_from(error: ApiError) -> Result<Self, Self::Error> {
+        match error {
+            ApiError::User(code) => Cep18Error::try_from(code),
+            _ => Err(()),
+        }
+    }
+}
 
 pub struct CEP18 {
     pub contract_hash: ContractHash,
+    pub version: Cep18Version,
 }
 
 impl CEP18 {
-    pub fn new(contract_hash: ContractHash) -> Self {
-        CEP18 { contract_hash }
+    pub fn new(contract_hash: ContractHash, version: Cep18Version) -> Self {
+        CEP18 {
+            contract_hash,
+            version,
+        }
     }
 
-    pub fn transfer(&self, recipient: Key, amount: U256) -> () {
+    pub fn transfer(&self, recipient: Key, amount: U256) {
         runtime::call_contract::<()>(
             self.contract_hash,
             "transfer",
@@ -24,7 +122,7 @@ impl CEP18 {
         )
     }
 
-    pub fn transfer_from(&self, sender: Key, recipient: Key, amount: U256) -> () {
+    pub fn transfer_from(&self, sender: Key, recipient: Key, amount: U256) {
         runtime::call_contract::<()>(
             self.contract_hash,
             "transfer_from",
@@ -36,7 +134,7 @@ impl CEP18 {
         )
     }
 
-    pub fn approve(&self, spender: Key, amount: U256) -> () {
+    pub fn approve(&self, spender: Key, amount: U256) {
         runtime::call_contract::<()>(
             self.contract_hash,
             "approve",
@@ -52,11 +150,80 @@ impl CEP18 {
     }
 
     pub fn balance_of(&self, address: Key) -> U256 {
+        let args = match self.version {
+            Cep18Version::V1 => runtime_args! { "address" => address },
+            Cep18Version::V2 => runtime_args! { "owner" => address },
+        };
+
+        runtime::call_contract::<U256>(self.contract_hash, "balance_of", args)
+    }
+
+    pub fn allowance(&self, owner: Key, spender: Key) -> U256 {
         runtime::call_contract::<U256>(
             self.contract_hash,
-            "balance_of",
+            "allowance",
             runtime_args! {
-                "address" => address
+                "owner" => owner,
+                "spender" => spender,
+            },
+        )
+    }
+
+    pub fn total_supply(&self) -> U256 {
+        runtime::call_contract::<U256>(self.contract_hash, "total_supply", runtime_args! {})
+    }
+
+    pub fn name(&self) -> String {
+        runtime::call_contract::<String>(self.contract_hash, "name", runtime_args! {})
+    }
+
+    pub fn symbol(&self) -> String {
+        runtime::call_contract::<String>(self.contract_hash, "symbol", runtime_args! {})
+    }
+
+    pub fn increase_allowance(&self, spender: Key, amount: U256) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "increase_allowance",
+            runtime_args! {
+                "spender" => spender,
+                "amount" => amount,
+            },
+        )
+    }
+
+    pub fn decrease_allowance(&self, spender: Key, amount: U256) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "decrease_allowance",
+            runtime_args! {
+                "spender" => spender,
+                "amount" => amount,
+            },
+        )
+    }
+
+    /// Requires the caller to be on the token's minter list.
+    pub fn mint(&self, owner: Key, amount: U256) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "mint",
+            runtime_args! {
+                "owner" => owner,
+                "amount" => amount,
+            },
+        )
+    }
+
+    /// Requires the caller to be on the token's minter list and `owner` to be
+    /// the caller itself.
+    pub fn burn(&self, owner: Key, amount: U256) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "burn",
+            runtime_args! {
+                "owner" => owner,
+                "amount" => amount,
             },
         )
     }
@@ -0,0 +1,88 @@
+#![allow(dead_code)]
+extern crate alloc;
+
+use alloc::string::String;
+use casper_contract::contract_api::runtime;
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs};
+
+// ref => https://github.com/casper-ecosystem/cep-78-enhanced-nft/blob/dev/contract/src/main.rs
+/// Client for a CEP-78 collection using ordinal token identifiers.
+pub struct CEP78 {
+    pub contract_hash: ContractHash,
+}
+
+impl CEP78 {
+    pub fn new(contract_hash: ContractHash) -> Self {
+        CEP78 { contract_hash }
+    }
+
+    pub fn collection_name(&self) -> String {
+        runtime::call_contract::<String>(self.contract_hash, "collection_name", runtime_args! {})
+    }
+
+    pub fn balance_of(&self, token_owner: Key) -> u64 {
+        runtime::call_contract::<u64>(
+            self.contract_hash,
+            "balance_of",
+            runtime_args! {
+                "token_owner" => token_owner,
+            },
+        )
+    }
+
+    pub fn owner_of(&self, token_id: u64) -> Key {
+        runtime::call_contract::<Key>(
+            self.contract_hash,
+            "owner_of",
+            runtime_args! {
+                "token_id" => token_id,
+            },
+        )
+    }
+
+    pub fn metadata(&self, token_id: u64) -> String {
+        runtime::call_contract::<String>(
+            self.contract_hash,
+            "metadata",
+            runtime_args! {
+                "token_id" => token_id,
+            },
+        )
+    }
+
+    /// Requires the caller to be allowed to mint by the collection's minting
+    /// mode. Returns the collection name, the owner's token dictionary key and
+    /// the new token id.
+    pub fn mint(&self, token_owner: Key, token_meta_data: String) -> (String, Key, String) {
+        runtime::call_contract::<(String, Key, String)>(
+            self.contract_hash,
+            "mint",
+            runtime_args! {
+                "token_owner" => token_owner,
+                "token_meta_data" => token_meta_data,
+            },
+        )
+    }
+
+    /// Requires the collection to have mutable metadata.
+    pub fn set_token_metadata(&self, token_id: u64, token_meta_data: String) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "set_token_metadata",
+            runtime_args! {
+                "token_id" => token_id,
+                "token_meta_data" => token_meta_data,
+            },
+        )
+    }
+
+    pub fn burn(&self, token_id: u64) {
+        runtime::call_contract::<()>(
+            self.contract_hash,
+            "burn",
+            runtime_args! {
+                "token_id" => token_id,
+            },
+        )
+    }
+}
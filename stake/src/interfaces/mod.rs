@@ -1 +1,2 @@
 pub mod cep18;
+pub mod cep78;
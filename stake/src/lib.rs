@@ -4,6 +4,7 @@
 extern crate alloc;
 mod enums;
 mod error;
+mod events;
 mod interfaces;
 mod stake;
 mod utils;
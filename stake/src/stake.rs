@@ -1,26 +1,39 @@
 use crate::enums::Address;
-use crate::interfaces::cep18::CEP18;
+use crate::events::{self, StakeEvent};
+use crate::interfaces::cep18::{Cep18Version, CEP18};
+use crate::interfaces::cep78::CEP78;
 use crate::{
     error::Error,
     utils::{self, get_current_address},
 };
 use alloc::{
+    boxed::Box,
+    format,
     string::{String, ToString},
     vec,
+    vec::Vec,
+};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
 };
-use casper_contract::contract_api::{runtime, storage};
 use casper_types::{
     account::AccountHash,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
     contracts::NamedKeys,
-    runtime_args,
+    crypto, runtime_args,
     CLType::{self, URef},
     ContractHash, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key, Parameter,
-    RuntimeArgs, U256,
+    PublicKey, RuntimeArgs, Signature, U256,
+};
+use core::{
+    convert::{TryFrom, TryInto},
+    ops::Div,
 };
-use core::ops::{Add, Div, Mul, Sub};
 
 // Variables
 const TOKEN: &str = "token";
+const TOKEN_VERSION: &str = "token_version";
 const FIXED_APR: &str = "fixed_apr";
 const MIN_APR: &str = "min_apr";
 const MAX_APR: &str = "max_apr";
@@ -31,6 +44,11 @@ const LOCK_PERIOD: &str = "lock_period";
 const DEPOSIT_START_TIME: &str = "deposit_start_time";
 const DEPOSIT_END_TIME: &str = "deposit_end_time";
 const AMOUNT: &str = "amount";
+const BENEFICIARY: &str = "beneficiary";
+const RECIPIENT: &str = "recipient";
+const STAKER: &str = "staker";
+const OPERATOR: &str = "operator";
+const TO: &str = "to";
 const TOTAL_SUPPLY: &str = "total_supply";
 const STORAGE_KEY: &str = "storage_key";
 const NOTIFIED: &str = "notified";
@@ -38,21 +56,102 @@ const OWNER: &str = "owner";
 const TOTAL_REWARD: &str = "total_reward";
 const LIQUIDITY: &str = "liquidity";
 const APR: &str = "apr";
+const TOTAL_LIABILITY: &str = "total_liability";
+const REENTRANCY_LOCK: &str = "reentrancy_lock";
+const REQUIRE_EXACT_TRANSFER: &str = "require_exact_transfer";
+const TRANSFERABLE: &str = "transferable";
+const RECEIPT_TOKEN: &str = "receipt_token";
+const RECEIPT_TOKEN_VERSION: &str = "receipt_token_version";
+const POSITION_NFT: &str = "position_nft";
+const TOKEN_ID: &str = "token_id";
+const VESTING_PERIOD: &str = "vesting_period";
+const VESTING_CLIFF: &str = "vesting_cliff";
+const INTERIM_CLAIMS: &str = "interim_claims";
+const UNBONDING_PERIOD: &str = "unbonding_period";
+const ALLOWLIST_ENABLED: &str = "allowlist_enabled";
+const ADDRESSES: &str = "addresses";
+const MERKLE_ROOT: &str = "merkle_root";
+const PROOF: &str = "proof";
+const STAKE_CAP: &str = "stake_cap";
+const VOUCHER_SIGNER: &str = "voucher_signer";
+const VOUCHER_BOOST_BPS: &str = "voucher_boost_bps";
+const VOUCHER_EXPIRY: &str = "voucher_expiry";
+const VOUCHER_NONCE: &str = "voucher_nonce";
+const VOUCHER_SIGNATURE: &str = "voucher_signature";
+const REFERRER: &str = "referrer";
+const REFERRAL_BPS: &str = "referral_bps";
+const FEE_BPS: &str = "fee_bps";
+const FEE_RECIPIENT: &str = "fee_recipient";
+const TOTAL_FEES: &str = "total_fees";
+const TOTAL_DEPOSIT_FEES: &str = "total_deposit_fees";
+const TOTAL_WITHDRAWAL_FEES: &str = "total_withdrawal_fees";
+const DEPOSIT_FEE_BPS: &str = "deposit_fee_bps";
+const WITHDRAWAL_FEE_BPS: &str = "withdrawal_fee_bps";
 
 // Dictionaries
 const STAKES_DICT: &str = "stakes_dict";
 const CLAIMED_DICT: &str = "claimed_dict";
 const STAKES_BALANCE_DICT: &str = "stakes_balance_dict";
+const REWARDS_DICT: &str = "rewards_dict";
+const STAKES_APR_DICT: &str = "stakes_apr_dict";
+const OPERATORS_DICT: &str = "operators_dict";
+const POSITION_TOKENS_DICT: &str = "position_tokens_dict";
+const TOKEN_POSITIONS_DICT: &str = "token_positions_dict";
+const LAST_CLAIM_TIME_DICT: &str = "last_claim_time_dict";
+const UNBONDING_DICT: &str = "unbonding_dict";
+const ALLOWLIST_DICT: &str = "allowlist_dict";
+const VOUCHER_NONCES_DICT: &str = "voucher_nonces_dict";
+const REFERRERS_DICT: &str = "referrers_dict";
+const REFERRAL_REWARDS_DICT: &str = "referral_rewards_dict";
+const UNLOCK_TIME_DICT: &str = "unlock_time_dict";
 
 // Entry points
 const ENTRY_POINT_NOTIFY: &str = "notify";
 const ENTRY_POINT_STAKE: &str = "stake";
+const ENTRY_POINT_STAKE_FOR: &str = "stake_for";
 const ENTRY_POINT_UNSTAKE: &str = "unstake";
+const ENTRY_POINT_REQUEST_UNSTAKE: &str = "request_unstake";
+const ENTRY_POINT_WITHDRAW: &str = "withdraw";
+const ENTRY_POINT_EXIT: &str = "exit";
 const ENTRY_POINT_CLAIM: &str = "claim";
+const ENTRY_POINT_COMPOUND: &str = "compound";
+const ENTRY_POINT_APPROVE_OPERATOR: &str = "approve_operator";
+const ENTRY_POINT_REVOKE_OPERATOR: &str = "revoke_operator";
+const ENTRY_POINT_TRANSFER_POSITION: &str = "transfer_position";
 const ENTRY_POINT_REFUND_REWARD: &str = "refund_reward";
+const ENTRY_POINT_ADD_REWARDS: &str = "add_rewards";
+const ENTRY_POINT_SET_MAX_CAP: &str = "set_max_cap";
+const ENTRY_POINT_SET_STAKE_LIMITS: &str = "set_stake_limits";
+const ENTRY_POINT_EXTEND_DEPOSIT_WINDOW: &str = "extend_deposit_window";
+const ENTRY_POINT_ADD_TO_ALLOWLIST: &str = "add_to_allowlist";
+const ENTRY_POINT_REMOVE_FROM_ALLOWLIST: &str = "remove_from_allowlist";
+const ENTRY_POINT_SET_MERKLE_ROOT: &str = "set_merkle_root";
+const ENTRY_POINT_SET_VOUCHER_SIGNER: &str = "set_voucher_signer";
+const ENTRY_POINT_CLAIM_REFERRAL_REWARDS: &str = "claim_referral_rewards";
+const ENTRY_POINT_SET_FEE_BPS: &str = "set_fee_bps";
 
 #[no_mangle]
 pub extern "C" fn stake() {
+    let staker: Address = utils::get_immediate_caller_address();
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+
+    deposit(staker.into(), staker.into(), amount);
+}
+
+#[no_mangle]
+pub extern "C" fn stake_for() {
+    let payer: Address = utils::get_immediate_caller_address();
+    let beneficiary: Key = runtime::get_named_arg(BENEFICIARY);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+
+    deposit(payer.into(), beneficiary, amount);
+}
+
+/// Pulls `amount` from `payer` and credits it to the position of `beneficiary`,
+/// whose own `max_stake` applies.
+fn deposit(payer: Key, beneficiary: Key, amount: U256) {
+    reentrancy_guard_enter();
+
     let notified: bool = utils::read_from(NOTIFIED);
 
     if !notified {
@@ -74,65 +173,245 @@ pub extern "C" fn stake() {
     }
 
     // amount limits
-    let amount: U256 = runtime::get_named_arg(AMOUNT);
-
     if amount.is_zero() {
         runtime::revert(Error::AmountIsZero);
     }
 
-    let min_stake: U256 = utils::read_from(MIN_STAKE);
+    // rejects anything that is neither an account nor a contract
+    let staker_item_key: String = utils::encode_dictionary_item_key(beneficiary);
 
-    if amount.lt(&min_stake) {
-        runtime::revert(Error::AmountLimits);
+    ensure_allowlisted(&staker_item_key);
+
+    let cep18: CEP18 = token_contract();
+    let payer_balance: U256 = cep18.balance_of(payer);
+
+    if payer_balance.lt(&amount) {
+        runtime::revert(Error::InsufficientBalance);
     }
 
-    let staker: AccountHash = runtime::get_caller();
+    ensure_allowance(&cep18, payer, amount);
 
-    let staker_item_key: String = utils::encode_dictionary_item_key(staker.into());
-    let stake_dict = *runtime::get_key(STAKES_DICT).unwrap().as_uref().unwrap();
-    let stake_balance: U256 = match storage::dictionary_get::<U256>(stake_dict, &staker_item_key) {
-        Ok(Some(stake)) => stake,
-        _ => U256::zero(),
+    if let Some(nft) = position_nft_contract() {
+        detach_sold_position(&nft, beneficiary, &staker_item_key);
+    }
+
+    // every deposit locks in the APR that is live when it is made, plus any
+    // boost granted by a voucher
+    let boost_bps: u64 = redeem_voucher(payer, beneficiary);
+    let referrer: Option<Key> = referrer_of(payer, beneficiary, &staker_item_key);
+    let base: DepositBase = deposit_base(
+        staker_item_key,
+        max_stake_of(beneficiary),
+        boost_bps,
+        referrer,
+    );
+
+    let credited: (U256, U256) = credit_deposit(&base, amount);
+
+    // only what actually arrived is credited, so a shortfall is credited again
+    // from the same base
+    let received: U256 = receive_tokens(&cep18, payer, amount);
+    let (amount, deposit_fee): (U256, U256) = if received.lt(&amount) {
+        credit_deposit(&base, received)
+    } else {
+        credited
     };
 
-    let total_staked_balance = stake_balance.add(amount);
-    let max_stake: U256 = utils::read_from(MAX_STAKE);
+    if let Some(receipt) = receipt_contract() {
+        receipt.mint(beneficiary, amount);
+    }
+
+    if let Some(nft) = position_nft_contract() {
+        let metadata: String = position_metadata(&base.staker_item_key);
+
+        match position_token_of(&base.staker_item_key) {
+            Some(token_id) => nft.set_token_metadata(token_id, metadata),
+            None => {
+                let (_, _, token_id) = nft.mint(beneficiary, metadata);
+                let token_id: u64 = token_id
+                    .parse()
+                    .ok()
+                    .unwrap_or_revert_with(Error::FatalError);
+
+                utils::dictionary_write(
+                    POSITION_TOKENS_DICT,
+                    &base.staker_item_key,
+                    Some(token_id),
+                );
+                utils::dictionary_write(TOKEN_POSITIONS_DICT, &token_id.to_string(), beneficiary);
+            }
+        }
+    }
 
-    if total_staked_balance.gt(&max_stake) {
+    pay_fee(&cep18, deposit_fee);
+
+    reentrancy_guard_exit();
+}
+
+/// Position and pool state a deposit is credited against. It is read before
+/// the deposit writes anything, so the deposit can be credited again for a
+/// different amount.
+struct DepositBase {
+    staker_item_key: String,
+    stake: U256,
+    reward: U256,
+    unlock_time: u64,
+    min_stake: U256,
+    max_stake: U256,
+    apr: u64,
+    boost_bps: u64,
+    deposit_fee_bps: u64,
+    total_deposit_fees: U256,
+    total_supply: U256,
+    liquidity: U256,
+    total_liability: U256,
+    referral: Option<(String, U256)>,
+}
+
+fn deposit_base(
+    staker_item_key: String,
+    max_stake: U256,
+    boost_bps: u64,
+    referrer: Option<Key>,
+) -> DepositBase {
+    let referral_bps: u64 = utils::read_from(REFERRAL_BPS);
+    let referral: Option<(String, U256)> = match referrer {
+        Some(referrer) if referral_bps > 0 => {
+            let referrer_item_key: String = utils::encode_dictionary_item_key(referrer);
+            let accrued: U256 = utils::dictionary_read(REFERRAL_REWARDS_DICT, &referrer_item_key)
+                .unwrap_or_default();
+
+            Some((referrer_item_key, accrued))
+        }
+        _ => None,
+    };
+
+    DepositBase {
+        stake: utils::dictionary_read(STAKES_DICT, &staker_item_key).unwrap_or_default(),
+        reward: utils::dictionary_read(REWARDS_DICT, &staker_item_key).unwrap_or_default(),
+        // a top-up moves the whole position onto the lock terms live right now
+        unlock_time: unlock_time_of(&staker_item_key).max(lock_expire_time()),
+        staker_item_key,
+        min_stake: utils::read_from(MIN_STAKE),
+        max_stake,
+        apr: utils::read_from(APR),
+        boost_bps,
+        deposit_fee_bps: utils::read_from(DEPOSIT_FEE_BPS),
+        total_deposit_fees: utils::read_from(TOTAL_DEPOSIT_FEES),
+        total_supply: utils::read_from(TOTAL_SUPPLY),
+        liquidity: utils::read_from(LIQUIDITY),
+        total_liability: utils::read_from(TOTAL_LIABILITY),
+        referral,
+    }
+}
+
+/// Checks the deposit limits for `amount` on top of `base` and writes the
+/// resulting position and pool state. Returns the principal credited after the
+/// deposit fee, and that fee. Everything is written from `base`, so crediting
+/// again replaces the previous credit instead of adding to it.
+fn credit_deposit(base: &DepositBase, amount: U256) -> (U256, U256) {
+    // the deposit fee comes off the principal, every limit below sees the net amount
+    let deposit_fee: U256 = bps_of(amount, base.deposit_fee_bps);
+    let amount: U256 = amount
+        .checked_sub(deposit_fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    if amount.lt(&base.min_stake) {
         runtime::revert(Error::AmountLimits);
     }
 
-    let total_supply: U256 = utils::read_from(TOTAL_SUPPLY);
-    let added_total_supply: U256 = total_supply.add(amount);
+    let total_staked_balance = base
+        .stake
+        .checked_add(amount)
+        .unwrap_or_revert_with(Error::Overflow);
+
+    if total_staked_balance.gt(&base.max_stake) {
+        runtime::revert(Error::AmountLimits);
+    }
+
+    let added_total_supply: U256 = base
+        .total_supply
+        .checked_add(amount)
+        .unwrap_or_revert_with(Error::Overflow);
     let max_cap: U256 = utils::read_from(MAX_CAP);
 
+    // the cap counts every principal ever credited, as the reward liability
+    // does, so stakers leaving never frees capacity the rewards don't cover
     if added_total_supply.gt(&max_cap) {
         runtime::revert(Error::MaxCapacityError);
     }
 
-    let token: Key = utils::read_from(TOKEN);
-    let cep18: CEP18 = CEP18::new(token.into_hash().map(ContractHash::new).unwrap());
-    let staker_balance: U256 = cep18.balance_of(staker.into());
+    let reward: U256 = amount
+        .checked_mul(U256::from(base.apr))
+        .unwrap_or_revert_with(Error::Overflow)
+        .div(U256::from(100))
+        .checked_add(bps_of(amount, base.boost_bps))
+        .unwrap_or_revert_with(Error::Overflow);
+    let referral_reward: U256 = match base.referral {
+        Some(_) => bps_of(reward, utils::read_from(REFERRAL_BPS)),
+        None => U256::zero(),
+    };
+    let added_total_liability: U256 = base
+        .total_liability
+        .checked_add(reward)
+        .and_then(|liability| liability.checked_add(referral_reward))
+        .unwrap_or_revert_with(Error::Overflow);
+    let total_reward: U256 = utils::read_from(TOTAL_REWARD);
 
-    if staker_balance.lt(&amount) {
-        runtime::revert(Error::InsufficientBalance);
+    if added_total_liability.gt(&total_reward) {
+        runtime::revert(Error::InsufficientRewardFunds);
     }
 
-    let contract_address: Address = get_current_address();
-
-    cep18.transfer_from(staker.into(), contract_address.into(), amount);
-
-    let stakes_balance_dict = *runtime::get_key(STAKES_BALANCE_DICT)
-        .unwrap()
-        .as_uref()
-        .unwrap();
-
-    storage::dictionary_put(stake_dict, &staker_item_key, total_staked_balance);
-    storage::dictionary_put(stakes_balance_dict, &staker_item_key, total_staked_balance);
+    let staker_item_key: &str = &base.staker_item_key;
+
+    utils::dictionary_write(STAKES_DICT, staker_item_key, total_staked_balance);
+    utils::dictionary_write(STAKES_BALANCE_DICT, staker_item_key, total_staked_balance);
+    utils::dictionary_write(UNLOCK_TIME_DICT, staker_item_key, base.unlock_time);
+    utils::dictionary_write(
+        REWARDS_DICT,
+        staker_item_key,
+        base.reward
+            .checked_add(reward)
+            .unwrap_or_revert_with(Error::Overflow),
+    );
+    update_position_apr(staker_item_key);
+
+    if let Some((referrer_item_key, accrued)) = &base.referral {
+        utils::dictionary_write(
+            REFERRAL_REWARDS_DICT,
+            referrer_item_key,
+            accrued
+                .checked_add(referral_reward)
+                .unwrap_or_revert_with(Error::Overflow),
+        );
+    }
 
+    runtime::put_key(
+        TOTAL_LIABILITY,
+        storage::new_uref(added_total_liability).into(),
+    );
     runtime::put_key(TOTAL_SUPPLY, storage::new_uref(added_total_supply).into());
 
-    runtime::put_key(LIQUIDITY, storage::new_uref(added_total_supply).into());
+    // stakers who already left took their principal out of the liquidity
+    runtime::put_key(
+        LIQUIDITY,
+        storage::new_uref(
+            base.liquidity
+                .checked_add(amount)
+                .unwrap_or_revert_with(Error::Overflow),
+        )
+        .into(),
+    );
+
+    runtime::put_key(
+        TOTAL_DEPOSIT_FEES,
+        storage::new_uref(
+            base.total_deposit_fees
+                .checked_add(deposit_fee)
+                .unwrap_or_revert_with(Error::Overflow),
+        )
+        .into(),
+    );
 
     let fixed_apr: u64 = utils::read_from(FIXED_APR);
 
@@ -142,231 +421,1278 @@ pub extern "C" fn stake() {
         let dynamic_apr = calculate_dynamic_apr(added_total_supply, max_cap, min_apr, max_apr);
         runtime::put_key(APR, storage::new_uref(dynamic_apr).into());
     }
+
+    (amount, deposit_fee)
 }
 
 #[no_mangle]
 pub extern "C" fn unstake() {
-    let deposit_end_time: u64 = utils::read_from(DEPOSIT_END_TIME);
-    let lock_period: u64 = utils::read_from(LOCK_PERIOD);
-    let expire_time = deposit_end_time.add(lock_period);
+    reentrancy_guard_enter();
+
+    let unbonding_period: u64 = utils::read_from(UNBONDING_PERIOD);
+
+    if unbonding_period > 0 {
+        runtime::revert(Error::UnbondingRequired);
+    }
+
+    let caller: Address = utils::get_immediate_caller_address();
+    let recipient: Key = recipient_or(caller.into());
+    let (staker, amount): (Key, Option<U256>) = redeemed_position(caller);
+    let stake_balance: U256 = release_stake(staker, caller.into(), amount);
+    let fee: U256 = take_fee(stake_balance, WITHDRAWAL_FEE_BPS, TOTAL_WITHDRAWAL_FEES);
+    let payout: U256 = stake_balance
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    let cep18: CEP18 = token_contract();
+
+    cep18.transfer(recipient, payout);
+    pay_fee(&cep18, fee);
+
+    reentrancy_guard_exit();
+}
+
+/// Starts unbonding the caller's whole principal. Rewards are fully accrued
+/// once the lock ends, so nothing accrues while unbonding.
+#[no_mangle]
+pub extern "C" fn request_unstake() {
+    reentrancy_guard_enter();
+
+    let unbonding_period: u64 = utils::read_from(UNBONDING_PERIOD);
+
+    if unbonding_period == 0 {
+        runtime::revert(Error::UnbondingDisabled);
+    }
+
+    let caller: Address = utils::get_immediate_caller_address();
+    let (staker, amount): (Key, Option<U256>) = redeemed_position(caller);
+    let stake_balance: U256 = release_stake(staker, caller.into(), amount);
+    let requester_item_key: String = utils::encode_dictionary_item_key(caller.into());
+    let (pending, _): (U256, u64) =
+        utils::dictionary_read(UNBONDING_DICT, &requester_item_key).unwrap_or_default();
+    let now: u64 = runtime::get_blocktime().into();
+    // a new request restarts the cooldown for everything pending
+    let release_time: u64 = now
+        .checked_add(unbonding_period)
+        .unwrap_or_revert_with(Error::Overflow);
+
+    utils::dictionary_write(
+        UNBONDING_DICT,
+        &requester_item_key,
+        (
+            pending
+                .checked_add(stake_balance)
+                .unwrap_or_revert_with(Error::Overflow),
+            release_time,
+        ),
+    );
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn withdraw() {
+    reentrancy_guard_enter();
+
+    let caller: Address = utils::get_immediate_caller_address();
+    let recipient: Key = recipient_or(caller.into());
+    let requester_item_key: String = utils::encode_dictionary_item_key(caller.into());
+    let (pending, release_time): (U256, u64) =
+        utils::dictionary_read(UNBONDING_DICT, &requester_item_key).unwrap_or_default();
+
+    if pending.is_zero() {
+        runtime::revert(Error::NoPendingUnstake);
+    }
+
+    let now: u64 = runtime::get_blocktime().into();
+
+    if release_time.gt(&now) {
+        runtime::revert(Error::StillUnbonding);
+    }
+
+    utils::dictionary_write(UNBONDING_DICT, &requester_item_key, (U256::zero(), 0u64));
+
+    let fee: U256 = take_fee(pending, WITHDRAWAL_FEE_BPS, TOTAL_WITHDRAWAL_FEES);
+    let payout: U256 = pending
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    let cep18: CEP18 = token_contract();
+
+    cep18.transfer(recipient, payout);
+    pay_fee(&cep18, fee);
+
+    reentrancy_guard_exit();
+}
+
+/// Unstakes and claims in one go: pays the principal together with every
+/// reward claimable right now and settles both. Unlike `unstake` it only ever
+/// acts on the caller's own position. Like `unstake` it is unavailable in pools
+/// with an unbonding period, where principal leaves through `request_unstake`
+/// and `withdraw` and rewards through `claim`.
+#[no_mangle]
+pub extern "C" fn exit() {
+    reentrancy_guard_enter();
+
+    let unbonding_period: u64 = utils::read_from(UNBONDING_PERIOD);
+
+    if unbonding_period > 0 {
+        runtime::revert(Error::UnbondingRequired);
+    }
+
+    let caller: Address = utils::get_immediate_caller_address();
+    let recipient: Key = recipient_or(caller.into());
+    let staker: Key = position_of(caller);
+    let staker_item_key: String = utils::encode_dictionary_item_key(staker);
+    let stake_balance: U256 = release_stake(staker, caller.into(), None);
+    let now: u64 = runtime::get_blocktime().into();
+    let claimable: U256 = take_claimable_rewards(&staker_item_key, now);
+    let fee: U256 = take_fee(claimable, FEE_BPS, TOTAL_FEES)
+        .checked_add(take_fee(
+            stake_balance,
+            WITHDRAWAL_FEE_BPS,
+            TOTAL_WITHDRAWAL_FEES,
+        ))
+        .unwrap_or_revert_with(Error::Overflow);
+    let payout: U256 = stake_balance
+        .checked_add(claimable)
+        .unwrap_or_revert_with(Error::Overflow)
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    let cep18: CEP18 = token_contract();
+
+    cep18.transfer(recipient, payout);
+    pay_fee(&cep18, fee);
+
+    reentrancy_guard_exit();
+}
+
+/// Takes `amount` of the staker's principal, or all of it, out of the pool
+/// once the lock has expired and returns it. The `holder` hands back as many
+/// receipts, which are burned here, paying out is left to the caller.
+fn release_stake(staker: Key, holder: Key, amount: Option<U256>) -> U256 {
+    let staker_item_key: String = utils::encode_dictionary_item_key(staker);
+    let expire_time: u64 = unlock_time_of(&staker_item_key);
     let now: u64 = runtime::get_blocktime().into();
 
     if expire_time.gt(&now) {
         runtime::revert(Error::StillLockPeriod);
     }
 
-    let staker: AccountHash = runtime::get_caller();
     let stake_dict = *runtime::get_key(STAKES_DICT).unwrap().as_uref().unwrap();
-    let staker_item_key: String = utils::encode_dictionary_item_key(staker.into());
 
     let stake_balance: U256 = match storage::dictionary_get::<U256>(stake_dict, &staker_item_key) {
         Ok(Some(stake)) => stake,
         _ => U256::zero(),
     };
 
-    if stake_balance.is_zero() {
+    let amount: U256 = amount.unwrap_or(stake_balance);
+
+    if amount.is_zero() || amount.gt(&stake_balance) {
         runtime::revert(Error::InsufficientStakeBalance);
     }
 
-    let liquidity: U256 = utils::read_from(LIQUIDITY);
+    let pool: Key = get_current_address().into();
+    let receipt: Option<CEP18> = receipt_contract();
 
-    let token: Key = utils::read_from(TOKEN);
-    let cep18: CEP18 = CEP18::new(token.into_hash().map(ContractHash::new).unwrap());
+    // receipts may have changed hands, whoever redeems has to hand back one
+    // receipt per token of principal released
+    if let Some(receipt) = &receipt {
+        if receipt.balance_of(holder).lt(&amount) {
+            runtime::revert(Error::InsufficientBalance);
+        }
+
+        if receipt.allowance(holder, pool).lt(&amount) {
+            runtime::revert(Error::InsufficientAllowance);
+        }
+    }
 
-    cep18.transfer(staker.into(), stake_balance);
+    let liquidity: U256 = utils::read_from(LIQUIDITY);
 
-    storage::dictionary_put(stake_dict, &staker_item_key, U256::zero());
+    storage::dictionary_put(
+        stake_dict,
+        &staker_item_key,
+        stake_balance
+            .checked_sub(amount)
+            .unwrap_or_revert_with(Error::Underflow),
+    );
     runtime::put_key(
         LIQUIDITY,
-        storage::new_uref(liquidity.sub(stake_balance)).into(),
+        storage::new_uref(
+            liquidity
+                .checked_sub(amount)
+                .unwrap_or_revert_with(Error::Underflow),
+        )
+        .into(),
     );
+
+    if let Some(receipt) = &receipt {
+        receipt.transfer_from(holder, pool, amount);
+        receipt.burn(pool, amount);
+    }
+
+    refresh_position_metadata(&staker_item_key);
+
+    amount
 }
 
 #[no_mangle]
 pub extern "C" fn claim() {
-    let notified: bool = utils::read_from(NOTIFIED);
+    reentrancy_guard_enter();
 
-    if !notified {
-        runtime::revert(Error::WaitingNotify);
-    }
+    let caller: Address = utils::get_immediate_caller_address();
+    let (staker, owner): (Key, Key) = authorized_staker(caller);
+    // operators can trigger a claim but never redirect it
+    let recipient: Key = if owner != caller.into() {
+        owner
+    } else {
+        recipient_or(caller.into())
+    };
+    let staker_item_key: String = utils::encode_dictionary_item_key(staker);
+    let claimable: U256 = settle_rewards(&staker_item_key);
+    let fee: U256 = take_fee(claimable, FEE_BPS, TOTAL_FEES);
+    let payout: U256 = claimable
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
 
-    let deposit_end_time: u64 = utils::read_from(DEPOSIT_END_TIME);
-    let lock_period: u64 = utils::read_from(LOCK_PERIOD);
-    let expire_time = deposit_end_time.add(lock_period);
-    let now: u64 = runtime::get_blocktime().into();
+    let cep18: CEP18 = token_contract();
 
-    if expire_time.gt(&now) {
-        runtime::revert(Error::StillLockPeriod);
-    }
+    cep18.transfer(recipient, payout);
+    pay_fee(&cep18, fee);
 
-    let staker: AccountHash = runtime::get_caller();
-    let staker_item_key: String = utils::encode_dictionary_item_key(staker.into());
-    let stake_dict = *runtime::get_key(STAKES_BALANCE_DICT)
-        .unwrap()
-        .as_uref()
-        .unwrap();
+    reentrancy_guard_exit();
+}
 
-    let stake: U256 = match storage::dictionary_get::<U256>(stake_dict, &staker_item_key) {
-        Ok(Some(stake)) => stake,
-        _ => U256::zero(),
+/// Turns the staker's claimable rewards, after the reward fee, into principal.
+/// The compounded amount goes through the same limits and accounting as a
+/// deposit, apart from `min_stake` and the deposit fee. It earns the live APR
+/// while the position is still locked and only adds principal once unlocked.
+#[no_mangle]
+pub extern "C" fn compound() {
+    reentrancy_guard_enter();
+
+    let caller: Address = utils::get_immediate_caller_address();
+    let (staker, owner): (Key, Key) = authorized_staker(caller);
+    let staker_item_key: String = utils::encode_dictionary_item_key(staker);
+    let claimable: U256 = settle_rewards(&staker_item_key);
+    let fee: U256 = take_fee(claimable, FEE_BPS, TOTAL_FEES);
+    let claimable: U256 = claimable
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    // the reward tokens already sit in the pool, they now back principal instead
+    let referrer: Option<Key> = utils::dictionary_read(REFERRERS_DICT, &staker_item_key);
+    let unlock_time: u64 = unlock_time_of(&staker_item_key);
+    let now: u64 = runtime::get_blocktime().into();
+    let base: DepositBase = deposit_base(staker_item_key, max_stake_of(owner), 0, referrer);
+    let base: DepositBase = DepositBase {
+        unlock_time,
+        min_stake: U256::zero(),
+        apr: if now < unlock_time { base.apr } else { 0 },
+        deposit_fee_bps: 0,
+        ..base
     };
+    let (claimable, _): (U256, U256) = credit_deposit(&base, claimable);
 
-    if stake.is_zero() {
-        runtime::revert(Error::StakeAmountIsZero);
+    if let Some(receipt) = receipt_contract() {
+        receipt.mint(staker, claimable);
     }
 
-    let apr: u64 = utils::read_from(APR);
-    let reward = stake.mul(U256::from(apr)).div(U256::from(100));
+    refresh_position_metadata(&base.staker_item_key);
 
-    let token: Key = utils::read_from(TOKEN);
-    let cep18: CEP18 = CEP18::new(token.into_hash().map(ContractHash::new).unwrap());
+    let cep18: CEP18 = token_contract();
 
-    cep18.transfer(staker.into(), reward);
+    pay_fee(&cep18, fee);
 
-    let staker_item_key: String = utils::encode_dictionary_item_key(staker.into());
-    let claimed_dict = *runtime::get_key(CLAIMED_DICT).unwrap().as_uref().unwrap();
+    reentrancy_guard_exit();
+}
 
-    storage::dictionary_put(claimed_dict, &staker_item_key, reward);
+#[no_mangle]
+pub extern "C" fn approve_operator() {
+    set_operator(true);
 }
 
 #[no_mangle]
-pub extern "C" fn refund_reward() {
-    only_owner();
+pub extern "C" fn revoke_operator() {
+    set_operator(false);
+}
 
-    let deposit_end_time: u64 = utils::read_from(DEPOSIT_END_TIME);
-    let lock_period: u64 = utils::read_from(LOCK_PERIOD);
+fn set_operator(approved: bool) {
+    reentrancy_guard_enter();
 
-    let now: u64 = runtime::get_blocktime().into();
+    let notified: bool = utils::read_from(NOTIFIED);
 
-    if now < deposit_end_time + lock_period {
-        runtime::revert(Error::RefundTimeError);
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
     }
 
-    let total_supply: U256 = utils::read_from(TOTAL_SUPPLY);
-    let total_reward: U256 = utils::read_from(TOTAL_REWARD);
-    let apr: u64 = utils::read_from(APR);
+    let staker: Key = utils::get_immediate_caller_address().into();
+    let operator: Key = runtime::get_named_arg(OPERATOR);
 
-    let reward = total_supply.mul(U256::from(apr)).div(U256::from(100));
-    let remain_reward = total_reward.sub(reward);
+    utils::validate_key(&operator);
 
-    let token: Key = utils::read_from(TOKEN);
-    let owner: AccountHash = runtime::get_caller();
+    utils::dictionary_write(
+        OPERATORS_DICT,
+        &utils::encode_dictionary_pair_key(staker, operator),
+        approved,
+    );
 
-    let cep18: CEP18 = CEP18::new(token.into_hash().map(ContractHash::new).unwrap());
-    cep18.transfer(owner.into(), remain_reward);
+    if approved {
+        events::emit(&StakeEvent::OperatorApproved { staker, operator });
+    } else {
+        events::emit(&StakeEvent::OperatorRevoked { staker, operator });
+    }
+
+    reentrancy_guard_exit();
 }
 
 #[no_mangle]
-pub extern "C" fn notify() {
-    only_owner();
+pub extern "C" fn transfer_position() {
+    reentrancy_guard_enter();
+
+    let transferable: bool = utils::read_from(TRANSFERABLE);
+
+    if !transferable {
+        runtime::revert(Error::TransfersDisabled);
+    }
+
+    // tokenized positions move by transferring their token
+    if receipt_contract().is_some() || position_nft_contract().is_some() {
+        runtime::revert(Error::PositionIsTokenized);
+    }
 
     let notified: bool = utils::read_from(NOTIFIED);
 
-    if notified {
-        runtime::revert(Error::AlreadyNotified);
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
     }
 
-    let fixed_apr: u64 = utils::read_from(FIXED_APR);
-    let max_apr: u64 = utils::read_from(MAX_APR);
-    let max_cap: U256 = utils::read_from(MAX_CAP);
+    let from: Key = utils::get_immediate_caller_address().into();
+    let to: Key = runtime::get_named_arg(TO);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
 
-    let prize;
+    if from == to {
+        runtime::revert(Error::SelfTransfer);
+    }
 
-    if fixed_apr > 0 {
-        let fixed_apr_u256 = U256::from(fixed_apr);
+    if amount.is_zero() {
+        runtime::revert(Error::AmountIsZero);
+    }
 
-        prize = max_cap.mul(fixed_apr_u256).div(U256::from(100));
-        runtime::put_key(APR, storage::new_uref(fixed_apr).into());
-    } else {
-        let max_apr_u256 = U256::from(max_apr);
+    let from_item_key: String = utils::encode_dictionary_item_key(from);
+    let to_item_key: String = utils::encode_dictionary_item_key(to);
 
-        prize = max_cap.mul(max_apr_u256).div(U256::from(100));
-        runtime::put_key(APR, storage::new_uref(max_apr).into());
+    ensure_allowlisted(&to_item_key);
+
+    let from_stake: U256 = utils::dictionary_read(STAKES_DICT, &from_item_key).unwrap_or_default();
+
+    if from_stake.lt(&amount) {
+        runtime::revert(Error::InsufficientStakeBalance);
     }
 
-    // check allowance
-    let owner: AccountHash = runtime::get_caller();
-    let token: Key = utils::read_from(TOKEN);
-    let cep18: CEP18 = CEP18::new(token.into_hash().map(ContractHash::new).unwrap());
-    let balance: U256 = cep18.balance_of(owner.into());
+    let to_stake: U256 = utils::dictionary_read(STAKES_DICT, &to_item_key).unwrap_or_default();
+    let to_total_stake: U256 = to_stake
+        .checked_add(amount)
+        .unwrap_or_revert_with(Error::Overflow);
+    let max_stake: U256 = max_stake_of(to);
 
-    if prize.gt(&balance) {
-        runtime::revert(Error::InsufficientBalance);
+    if to_total_stake.gt(&max_stake) {
+        runtime::revert(Error::AmountLimits);
     }
 
-    let contract_address: Address = get_current_address();
+    utils::dictionary_write(
+        STAKES_DICT,
+        &from_item_key,
+        from_stake
+            .checked_sub(amount)
+            .unwrap_or_revert_with(Error::Underflow),
+    );
+    utils::dictionary_write(STAKES_DICT, &to_item_key, to_total_stake);
+
+    // the reward side of the position moves in the same proportion as the
+    // principal, so unclaimed rewards travel with it and totals stay unchanged
+    for dictionary_name in [STAKES_BALANCE_DICT, REWARDS_DICT, CLAIMED_DICT] {
+        let from_value: U256 =
+            utils::dictionary_read(dictionary_name, &from_item_key).unwrap_or_default();
+        let to_value: U256 =
+            utils::dictionary_read(dictionary_name, &to_item_key).unwrap_or_default();
+        let moved: U256 = from_value
+            .checked_mul(amount)
+            .unwrap_or_revert_with(Error::Overflow)
+            .div(from_stake);
+
+        utils::dictionary_write(
+            dictionary_name,
+            &from_item_key,
+            from_value
+                .checked_sub(moved)
+                .unwrap_or_revert_with(Error::Underflow),
+        );
+        utils::dictionary_write(
+            dictionary_name,
+            &to_item_key,
+            to_value
+                .checked_add(moved)
+                .unwrap_or_revert_with(Error::Overflow),
+        );
+    }
 
-    cep18.transfer_from(owner.into(), contract_address.into(), prize);
+    // the receiver continues from the later claim cursor, whatever is left
+    // unclaimed still accrues in full by the lock end
+    let from_last_claim_time: u64 =
+        utils::dictionary_read(LAST_CLAIM_TIME_DICT, &from_item_key).unwrap_or_default();
+    let to_last_claim_time: u64 =
+        utils::dictionary_read(LAST_CLAIM_TIME_DICT, &to_item_key).unwrap_or_default();
 
-    storage::new_dictionary(STAKES_DICT).unwrap_or_default();
-    storage::new_dictionary(STAKES_BALANCE_DICT).unwrap_or_default();
-    storage::new_dictionary(CLAIMED_DICT).unwrap_or_default();
-    runtime::put_key(TOTAL_SUPPLY, storage::new_uref(U256::zero()).into());
-    runtime::put_key(LIQUIDITY, storage::new_uref(U256::zero()).into());
-    runtime::put_key(TOTAL_REWARD, storage::new_uref(prize).into());
-    runtime::put_key(NOTIFIED, storage::new_uref(true).into());
-}
+    if from_last_claim_time > to_last_claim_time {
+        utils::dictionary_write(LAST_CLAIM_TIME_DICT, &to_item_key, from_last_claim_time);
+    }
 
-#[no_mangle]
-pub extern "C" fn call() {
-    let token: Key = runtime::get_named_arg(TOKEN);
-    let max_cap: U256 = runtime::get_named_arg(MAX_CAP);
-    let min_stake: U256 = runtime::get_named_arg(MIN_STAKE);
-    let max_stake: U256 = runtime::get_named_arg(MAX_STAKE);
+    // moved principal never unlocks earlier than it would have for the sender
+    let from_unlock_time: u64 = unlock_time_of(&from_item_key);
 
-    let fixed_apr: u64 = runtime::get_named_arg(FIXED_APR);
-    let min_apr: u64 = runtime::get_named_arg(MIN_APR);
-    let max_apr: u64 = runtime::get_named_arg(MAX_APR);
+    if to_stake.is_zero() || from_unlock_time > unlock_time_of(&to_item_key) {
+        utils::dictionary_write(UNLOCK_TIME_DICT, &to_item_key, from_unlock_time);
+    }
 
-    let lock_period: u64 = runtime::get_named_arg(LOCK_PERIOD);
-    let deposit_start_time: u64 = runtime::get_named_arg(DEPOSIT_START_TIME);
-    let deposit_end_time: u64 = runtime::get_named_arg(DEPOSIT_END_TIME);
-    let storage_key: ContractHash = runtime::get_named_arg(STORAGE_KEY);
-    let owner: AccountHash = runtime::get_caller();
+    update_position_apr(&from_item_key);
+    update_position_apr(&to_item_key);
 
-    let mut named_keys = NamedKeys::new();
+    events::emit(&StakeEvent::PositionTransferred { from, to, amount });
 
-    named_keys.insert(TOKEN.to_string(), storage::new_uref(token).into());
-    named_keys.insert(FIXED_APR.to_string(), storage::new_uref(fixed_apr).into());
-    named_keys.insert(MIN_APR.to_string(), storage::new_uref(min_apr).into());
-    named_keys.insert(MAX_APR.to_string(), storage::new_uref(max_apr).into());
-    named_keys.insert(MAX_CAP.to_string(), storage::new_uref(max_cap).into());
-    named_keys.insert(MIN_STAKE.to_string(), storage::new_uref(min_stake).into());
-    named_keys.insert(MAX_STAKE.to_string(), storage::new_uref(max_stake).into());
-    named_keys.insert(
-        LOCK_PERIOD.to_string(),
-        storage::new_uref(lock_period).into(),
-    );
-    named_keys.insert(
-        DEPOSIT_START_TIME.to_string(),
-        storage::new_uref(deposit_start_time).into(),
-    );
-    named_keys.insert(
-        DEPOSIT_END_TIME.to_string(),
-        storage::new_uref(deposit_end_time).into(),
-    );
-    named_keys.insert(NOTIFIED.to_string(), storage::new_uref(false).into());
-    named_keys.insert(OWNER.to_string(), storage::new_uref(owner).into());
+    reentrancy_guard_exit();
+}
 
-    let notify_entry_point: EntryPoint = EntryPoint::new(
-        ENTRY_POINT_NOTIFY,
-        vec![],
-        URef,
+/// Checks that rewards can be claimed for the staker, marks everything accrued
+/// so far as claimed and returns that amount.
+fn settle_rewards(staker_item_key: &str) -> U256 {
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
+    }
+
+    let expire_time: u64 = unlock_time_of(staker_item_key);
+    let now: u64 = runtime::get_blocktime().into();
+    let interim_claims: bool = utils::read_from(INTERIM_CLAIMS);
+
+    if expire_time.gt(&now) && !interim_claims {
+        runtime::revert(Error::StillLockPeriod);
+    }
+
+    let stake_dict = *runtime::get_key(STAKES_BALANCE_DICT)
+        .unwrap()
+        .as_uref()
+        .unwrap();
+
+    let stake: U256 = match storage::dictionary_get::<U256>(stake_dict, staker_item_key) {
+        Ok(Some(stake)) => stake,
+        _ => U256::zero(),
+    };
+
+    if stake.is_zero() {
+        runtime::revert(Error::StakeAmountIsZero);
+    }
+
+    let claimable: U256 = take_claimable_rewards(staker_item_key, now);
+
+    if claimable.is_zero() {
+        runtime::revert(Error::RewardIsZero);
+    }
+
+    claimable
+}
+
+/// Marks the staker's rewards claimable at `now` as claimed and returns them,
+/// which may be nothing.
+fn take_claimable_rewards(staker_item_key: &str, now: u64) -> U256 {
+    let interim_claims: bool = utils::read_from(INTERIM_CLAIMS);
+    let reward: U256 = utils::dictionary_read(REWARDS_DICT, staker_item_key).unwrap_or_default();
+    let claimed: U256 = utils::dictionary_read(CLAIMED_DICT, staker_item_key).unwrap_or_default();
+    let claimable: U256 = if interim_claims {
+        let unclaimed: U256 = reward
+            .checked_sub(claimed)
+            .unwrap_or_revert_with(Error::Underflow);
+
+        accrued_since_last_claim(staker_item_key, unclaimed, now)
+    } else {
+        vested_amount(reward, unlock_time_of(staker_item_key), now)
+            .checked_sub(claimed)
+            .unwrap_or_revert_with(Error::Underflow)
+    };
+
+    utils::dictionary_write(
+        CLAIMED_DICT,
+        staker_item_key,
+        claimed
+            .checked_add(claimable)
+            .unwrap_or_revert_with(Error::Overflow),
+    );
+
+    claimable
+}
+
+/// Whose rewards the caller acts on, and who they belong to: the caller's own,
+/// or those of the optional `staker` argument when the caller is an operator
+/// approved by that staker. With position NFTs it is the position behind the
+/// `token_id` argument, which belongs to the token's holder and can be acted
+/// on by operators the holder approved.
+fn authorized_staker(caller: Address) -> (Key, Key) {
+    let caller: Key = caller.into();
+    let (staker, owner): (Key, Key) = match position_nft_contract() {
+        Some(nft) => tokenized_position(&nft),
+        None => {
+            let staker: Key = utils::get_optional_named_arg(STAKER).unwrap_or(caller);
+
+            (staker, staker)
+        }
+    };
+
+    if owner == caller {
+        return (staker, owner);
+    }
+
+    let approved: bool = utils::dictionary_read(
+        OPERATORS_DICT,
+        &utils::encode_dictionary_pair_key(owner, caller),
+    )
+    .unwrap_or_default();
+
+    if !approved {
+        runtime::revert(Error::NotOperator);
+    }
+
+    (staker, owner)
+}
+
+#[no_mangle]
+pub extern "C" fn claim_referral_rewards() {
+    reentrancy_guard_enter();
+
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
+    }
+
+    let expire_time: u64 = lock_expire_time();
+    let now: u64 = runtime::get_blocktime().into();
+
+    if expire_time.gt(&now) {
+        runtime::revert(Error::StillLockPeriod);
+    }
+
+    let referrer: Address = utils::get_immediate_caller_address();
+    let recipient: Key = recipient_or(referrer.into());
+    let referrer_item_key: String = utils::encode_dictionary_item_key(referrer.into());
+    let referral_reward: U256 =
+        utils::dictionary_read(REFERRAL_REWARDS_DICT, &referrer_item_key).unwrap_or_default();
+
+    if referral_reward.is_zero() {
+        runtime::revert(Error::RewardIsZero);
+    }
+
+    utils::dictionary_write(REFERRAL_REWARDS_DICT, &referrer_item_key, U256::zero());
+
+    let fee: U256 = take_fee(referral_reward, FEE_BPS, TOTAL_FEES);
+    let payout: U256 = referral_reward
+        .checked_sub(fee)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    let cep18: CEP18 = token_contract();
+
+    cep18.transfer(recipient, payout);
+    pay_fee(&cep18, fee);
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn refund_reward() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let now: u64 = runtime::get_blocktime().into();
+
+    if now < lock_expire_time() {
+        runtime::revert(Error::RefundTimeError);
+    }
+
+    let total_reward: U256 = utils::read_from(TOTAL_REWARD);
+    let total_liability: U256 = utils::read_from(TOTAL_LIABILITY);
+
+    let remain_reward = total_reward
+        .checked_sub(total_liability)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    // whatever is left in the pool is owed to stakers
+    runtime::put_key(TOTAL_REWARD, storage::new_uref(total_liability).into());
+
+    let owner: AccountHash = runtime::get_caller();
+
+    let cep18: CEP18 = token_contract();
+    cep18.transfer(owner.into(), remain_reward);
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn add_rewards() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
+    }
+
+    let lock_period: u64 = utils::read_from(LOCK_PERIOD);
+    let now: u64 = runtime::get_blocktime().into();
+
+    if now >= lock_expire_time() {
+        runtime::revert(Error::RewardPeriodEnded);
+    }
+
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+    let new_apr: Option<u64> = utils::get_optional_named_arg(APR);
+    let new_lock_period: Option<u64> = utils::get_optional_named_arg(LOCK_PERIOD);
+
+    let total_reward: U256 = utils::read_from(TOTAL_REWARD);
+    let mut added_total_reward: U256 = total_reward;
+    let owner: AccountHash = runtime::get_caller();
+    let cep18: CEP18 = token_contract();
+
+    if !amount.is_zero() {
+        let balance: U256 = cep18.balance_of(owner.into());
+
+        if amount.gt(&balance) {
+            runtime::revert(Error::InsufficientBalance);
+        }
+
+        ensure_allowance(&cep18, owner.into(), amount);
+
+        added_total_reward = total_reward
+            .checked_add(amount)
+            .unwrap_or_revert_with(Error::Overflow);
+
+        runtime::put_key(TOTAL_REWARD, storage::new_uref(added_total_reward).into());
+    }
+
+    let fixed_apr: u64 = utils::read_from(FIXED_APR);
+    let mut ceiling_apr: u64 = ceiling_apr();
+
+    if let Some(new_apr) = new_apr {
+        if new_apr < ceiling_apr {
+            runtime::revert(Error::InvalidApr);
+        }
+
+        ceiling_apr = new_apr;
+
+        if fixed_apr > 0 {
+            runtime::put_key(FIXED_APR, storage::new_uref(new_apr).into());
+            runtime::put_key(APR, storage::new_uref(new_apr).into());
+        } else {
+            let total_supply: U256 = utils::read_from(TOTAL_SUPPLY);
+            let max_cap: U256 = utils::read_from(MAX_CAP);
+            let min_apr: u64 = utils::read_from(MIN_APR);
+            let dynamic_apr = calculate_dynamic_apr(total_supply, max_cap, min_apr, new_apr);
+
+            runtime::put_key(MAX_APR, storage::new_uref(new_apr).into());
+            runtime::put_key(APR, storage::new_uref(dynamic_apr).into());
+        }
+    }
+
+    if let Some(new_lock_period) = new_lock_period {
+        if new_lock_period < lock_period {
+            runtime::revert(Error::InvalidLockPeriod);
+        }
+
+        // existing positions keep their unlock time, only new deposits get it
+        runtime::put_key(LOCK_PERIOD, storage::new_uref(new_lock_period).into());
+    }
+
+    let max_cap: U256 = utils::read_from(MAX_CAP);
+
+    ensure_rewards_cover_capacity(added_total_reward, max_cap, ceiling_apr);
+
+    if !amount.is_zero() {
+        let received: U256 = receive_tokens(&cep18, owner.into(), amount);
+
+        // only what actually arrived backs the rewards
+        if received.lt(&amount) {
+            let added_total_reward: U256 = total_reward
+                .checked_add(received)
+                .unwrap_or_revert_with(Error::Overflow);
+
+            runtime::put_key(TOTAL_REWARD, storage::new_uref(added_total_reward).into());
+            ensure_rewards_cover_capacity(added_total_reward, max_cap, ceiling_apr);
+        }
+    }
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn set_max_cap() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let max_cap: U256 = runtime::get_named_arg(MAX_CAP);
+    let max_stake: U256 = utils::read_from(MAX_STAKE);
+
+    if max_cap.is_zero() || max_cap.lt(&max_stake) {
+        runtime::revert(Error::InvalidMaxCap);
+    }
+
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if notified {
+        let total_supply: U256 = utils::read_from(TOTAL_SUPPLY);
+
+        if max_cap.lt(&total_supply) {
+            runtime::revert(Error::InvalidMaxCap);
+        }
+
+        let total_reward: U256 = utils::read_from(TOTAL_REWARD);
+
+        ensure_rewards_cover_capacity(total_reward, max_cap, ceiling_apr());
+
+        let fixed_apr: u64 = utils::read_from(FIXED_APR);
+
+        if fixed_apr == 0 {
+            let min_apr = utils::read_from(MIN_APR);
+            let max_apr = utils::read_from(MAX_APR);
+            let dynamic_apr = calculate_dynamic_apr(total_supply, max_cap, min_apr, max_apr);
+            runtime::put_key(APR, storage::new_uref(dynamic_apr).into());
+        }
+    }
+
+    runtime::put_key(MAX_CAP, storage::new_uref(max_cap).into());
+
+    events::emit(&StakeEvent::MaxCapUpdated { max_cap });
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn set_stake_limits() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let min_stake: U256 = runtime::get_named_arg(MIN_STAKE);
+    let max_stake: U256 = runtime::get_named_arg(MAX_STAKE);
+    let max_cap: U256 = utils::read_from(MAX_CAP);
+
+    validate_stake_limits(min_stake, max_stake, max_cap);
+
+    runtime::put_key(MIN_STAKE, storage::new_uref(min_stake).into());
+    runtime::put_key(MAX_STAKE, storage::new_uref(max_stake).into());
+
+    events::emit(&StakeEvent::StakeLimitsUpdated {
+        min_stake,
+        max_stake,
+    });
+
+    reentrancy_guard_exit();
+}
+
+/// Pushes back the end of the deposit window. Positions already staked keep
+/// the unlock time they were deposited with, only later deposits lock until
+/// the new window end plus the lock period.
+#[no_mangle]
+pub extern "C" fn extend_deposit_window() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let current_deposit_end_time: u64 = utils::read_from(DEPOSIT_END_TIME);
+    let now: u64 = runtime::get_blocktime().into();
+
+    // a closed window has already started counting down the locks
+    if current_deposit_end_time < now {
+        runtime::revert(Error::StakeIsCompleted);
+    }
+
+    let deposit_end_time: u64 = runtime::get_named_arg(DEPOSIT_END_TIME);
+
+    if deposit_end_time <= current_deposit_end_time {
+        runtime::revert(Error::InvalidDepositWindow);
+    }
+
+    runtime::put_key(DEPOSIT_END_TIME, storage::new_uref(deposit_end_time).into());
+
+    events::emit(&StakeEvent::DepositWindowExtended { deposit_end_time });
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn add_to_allowlist() {
+    set_allowlisted(true);
+}
+
+#[no_mangle]
+pub extern "C" fn remove_from_allowlist() {
+    set_allowlisted(false);
+}
+
+fn set_allowlisted(allowed: bool) {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if !notified {
+        runtime::revert(Error::WaitingNotify);
+    }
+
+    let addresses: Vec<Key> = runtime::get_named_arg(ADDRESSES);
+
+    for address in addresses {
+        utils::dictionary_write(
+            ALLOWLIST_DICT,
+            &utils::encode_dictionary_item_key(address),
+            allowed,
+        );
+    }
+
+    reentrancy_guard_exit();
+}
+
+/// Gates staking behind a Merkle allowlist. An all-zero root removes the gate.
+#[no_mangle]
+pub extern "C" fn set_merkle_root() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let merkle_root: [u8; 32] = runtime::get_named_arg(MERKLE_ROOT);
+    let merkle_root: Option<[u8; 32]> = if merkle_root == [0u8; 32] {
+        None
+    } else {
+        Some(merkle_root)
+    };
+
+    runtime::put_key(MERKLE_ROOT, storage::new_uref(merkle_root).into());
+
+    events::emit(&StakeEvent::MerkleRootUpdated { merkle_root });
+
+    reentrancy_guard_exit();
+}
+
+/// Sets the key vouchers have to be signed with. `None` stops accepting
+/// vouchers.
+#[no_mangle]
+pub extern "C" fn set_voucher_signer() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let voucher_signer: Option<PublicKey> = runtime::get_named_arg(VOUCHER_SIGNER);
+
+    runtime::put_key(
+        VOUCHER_SIGNER,
+        storage::new_uref(voucher_signer.clone()).into(),
+    );
+
+    events::emit(&StakeEvent::VoucherSignerUpdated { voucher_signer });
+
+    reentrancy_guard_exit();
+}
+
+/// Lowers the protocol fee on rewards, it can never be raised again.
+#[no_mangle]
+pub extern "C" fn set_fee_bps() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let fee_bps: u64 = runtime::get_named_arg(FEE_BPS);
+    let current_fee_bps: u64 = utils::read_from(FEE_BPS);
+
+    if fee_bps > current_fee_bps {
+        runtime::revert(Error::FeeIncreaseNotAllowed);
+    }
+
+    runtime::put_key(FEE_BPS, storage::new_uref(fee_bps).into());
+
+    events::emit(&StakeEvent::FeeBpsUpdated { fee_bps });
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn notify() {
+    only_owner();
+    reentrancy_guard_enter();
+
+    let notified: bool = utils::read_from(NOTIFIED);
+
+    if notified {
+        runtime::revert(Error::AlreadyNotified);
+    }
+
+    let fixed_apr: u64 = utils::read_from(FIXED_APR);
+    let max_apr: u64 = utils::read_from(MAX_APR);
+    let max_cap: U256 = utils::read_from(MAX_CAP);
+
+    let prize;
+
+    if fixed_apr > 0 {
+        let fixed_apr_u256 = U256::from(fixed_apr);
+
+        prize = max_cap
+            .checked_mul(fixed_apr_u256)
+            .unwrap_or_revert_with(Error::Overflow)
+            .div(U256::from(100));
+        runtime::put_key(APR, storage::new_uref(fixed_apr).into());
+    } else {
+        let max_apr_u256 = U256::from(max_apr);
+
+        prize = max_cap
+            .checked_mul(max_apr_u256)
+            .unwrap_or_revert_with(Error::Overflow)
+            .div(U256::from(100));
+        runtime::put_key(APR, storage::new_uref(max_apr).into());
+    }
+
+    let prize: U256 = with_referral_share(prize);
+
+    let owner: AccountHash = runtime::get_caller();
+    let cep18: CEP18 = token_contract();
+    let balance: U256 = cep18.balance_of(owner.into());
+
+    if prize.gt(&balance) {
+        runtime::revert(Error::InsufficientBalance);
+    }
+
+    ensure_allowance(&cep18, owner.into(), prize);
+
+    storage::new_dictionary(STAKES_DICT).unwrap_or_default();
+    storage::new_dictionary(STAKES_BALANCE_DICT).unwrap_or_default();
+    storage::new_dictionary(CLAIMED_DICT).unwrap_or_default();
+    storage::new_dictionary(REWARDS_DICT).unwrap_or_default();
+    storage::new_dictionary(STAKES_APR_DICT).unwrap_or_default();
+    storage::new_dictionary(OPERATORS_DICT).unwrap_or_default();
+    storage::new_dictionary(POSITION_TOKENS_DICT).unwrap_or_default();
+    storage::new_dictionary(TOKEN_POSITIONS_DICT).unwrap_or_default();
+    storage::new_dictionary(LAST_CLAIM_TIME_DICT).unwrap_or_default();
+    storage::new_dictionary(UNBONDING_DICT).unwrap_or_default();
+    storage::new_dictionary(ALLOWLIST_DICT).unwrap_or_default();
+    storage::new_dictionary(VOUCHER_NONCES_DICT).unwrap_or_default();
+    storage::new_dictionary(REFERRERS_DICT).unwrap_or_default();
+    storage::new_dictionary(REFERRAL_REWARDS_DICT).unwrap_or_default();
+    storage::new_dictionary(UNLOCK_TIME_DICT).unwrap_or_default();
+    runtime::put_key(TOTAL_SUPPLY, storage::new_uref(U256::zero()).into());
+    runtime::put_key(LIQUIDITY, storage::new_uref(U256::zero()).into());
+    runtime::put_key(TOTAL_REWARD, storage::new_uref(prize).into());
+    runtime::put_key(TOTAL_LIABILITY, storage::new_uref(U256::zero()).into());
+    runtime::put_key(NOTIFIED, storage::new_uref(true).into());
+
+    let received: U256 = receive_tokens(&cep18, owner.into(), prize);
+
+    // only what actually arrived backs the rewards
+    if received.lt(&prize) {
+        runtime::put_key(TOTAL_REWARD, storage::new_uref(received).into());
+    }
+
+    reentrancy_guard_exit();
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let token: Key = runtime::get_named_arg(TOKEN);
+    let max_cap: U256 = runtime::get_named_arg(MAX_CAP);
+    let min_stake: U256 = runtime::get_named_arg(MIN_STAKE);
+    let max_stake: U256 = runtime::get_named_arg(MAX_STAKE);
+
+    let fixed_apr: u64 = runtime::get_named_arg(FIXED_APR);
+    let min_apr: u64 = runtime::get_named_arg(MIN_APR);
+    let max_apr: u64 = runtime::get_named_arg(MAX_APR);
+
+    let lock_period: u64 = runtime::get_named_arg(LOCK_PERIOD);
+    let deposit_start_time: u64 = runtime::get_named_arg(DEPOSIT_START_TIME);
+    let deposit_end_time: u64 = runtime::get_named_arg(DEPOSIT_END_TIME);
+    let storage_key: ContractHash = runtime::get_named_arg(STORAGE_KEY);
+    let token_version: u8 =
+        utils::get_optional_named_arg(TOKEN_VERSION).unwrap_or(Cep18Version::V1 as u8);
+    let require_exact_transfer: bool =
+        utils::get_optional_named_arg(REQUIRE_EXACT_TRANSFER).unwrap_or_default();
+    let transferable: bool = utils::get_optional_named_arg(TRANSFERABLE).unwrap_or(true);
+    let receipt_token: Option<Key> = utils::get_optional_named_arg(RECEIPT_TOKEN);
+    let receipt_token_version: u8 =
+        utils::get_optional_named_arg(RECEIPT_TOKEN_VERSION).unwrap_or(Cep18Version::V2 as u8);
+    let position_nft: Option<Key> = utils::get_optional_named_arg(POSITION_NFT);
+    let vesting_period: u64 = utils::get_optional_named_arg(VESTING_PERIOD).unwrap_or_default();
+    let vesting_cliff: u64 = utils::get_optional_named_arg(VESTING_CLIFF).unwrap_or_default();
+    let interim_claims: bool = utils::get_optional_named_arg(INTERIM_CLAIMS).unwrap_or_default();
+    let unbonding_period: u64 = utils::get_optional_named_arg(UNBONDING_PERIOD).unwrap_or_default();
+    let allowlist_enabled: bool =
+        utils::get_optional_named_arg(ALLOWLIST_ENABLED).unwrap_or_default();
+    let referral_bps: u64 = utils::get_optional_named_arg(REFERRAL_BPS).unwrap_or_default();
+    let owner: AccountHash = runtime::get_caller();
+    let fee_bps: u64 = utils::get_optional_named_arg(FEE_BPS).unwrap_or_default();
+    let deposit_fee_bps: u64 = utils::get_optional_named_arg(DEPOSIT_FEE_BPS).unwrap_or_default();
+    let withdrawal_fee_bps: u64 =
+        utils::get_optional_named_arg(WITHDRAWAL_FEE_BPS).unwrap_or_default();
+    let fee_recipient: Key = utils::get_optional_named_arg(FEE_RECIPIENT).unwrap_or(owner.into());
+
+    if token.into_hash().is_none() {
+        runtime::revert(Error::InvalidKey);
+    }
+
+    if let Some(receipt_token) = receipt_token {
+        if receipt_token.into_hash().is_none() || receipt_token == token {
+            runtime::revert(Error::InvalidKey);
+        }
+    }
+
+    if let Some(position_nft) = position_nft {
+        if position_nft.into_hash().is_none() {
+            runtime::revert(Error::InvalidKey);
+        }
+    }
+
+    if receipt_token.is_some() && position_nft.is_some() {
+        runtime::revert(Error::ConflictingPositionTokens);
+    }
+
+    if Cep18Version::try_from(token_version).is_err()
+        || Cep18Version::try_from(receipt_token_version).is_err()
+    {
+        runtime::revert(Error::InvalidTokenVersion);
+    }
+
+    if max_cap.is_zero() {
+        runtime::revert(Error::InvalidMaxCap);
+    }
+
+    validate_stake_limits(min_stake, max_stake, max_cap);
+
+    if min_apr > max_apr {
+        runtime::revert(Error::InvalidAprRange);
+    }
+
+    if deposit_end_time <= deposit_start_time {
+        runtime::revert(Error::InvalidDepositWindow);
+    }
+
+    // every unlock time is the window end plus the lock period
+    if deposit_end_time.checked_add(lock_period).is_none() {
+        runtime::revert(Error::InvalidLockPeriod);
+    }
+
+    if vesting_cliff > vesting_period {
+        runtime::revert(Error::InvalidVestingSchedule);
+    }
+
+    if interim_claims && vesting_period > 0 {
+        runtime::revert(Error::ConflictingRewardSchedules);
+    }
+
+    if referral_bps > 10_000 {
+        runtime::revert(Error::InvalidReferralBps);
+    }
+
+    // referral rewards neither vest nor accrue, they are paid whole once the
+    // locks have ended
+    if referral_bps > 0 && (vesting_period > 0 || interim_claims) {
+        runtime::revert(Error::ConflictingRewardSchedules);
+    }
+
+    // a deposit fee of 100% would leave nothing to stake
+    if fee_bps > 10_000 || deposit_fee_bps >= 10_000 || withdrawal_fee_bps > 10_000 {
+        runtime::revert(Error::InvalidFeeBps);
+    }
+
+    utils::validate_key(&fee_recipient);
+
+    let mut named_keys = NamedKeys::new();
+
+    named_keys.insert(TOKEN.to_string(), storage::new_uref(token).into());
+    named_keys.insert(
+        TOKEN_VERSION.to_string(),
+        storage::new_uref(token_version).into(),
+    );
+    named_keys.insert(FIXED_APR.to_string(), storage::new_uref(fixed_apr).into());
+    named_keys.insert(MIN_APR.to_string(), storage::new_uref(min_apr).into());
+    named_keys.insert(MAX_APR.to_string(), storage::new_uref(max_apr).into());
+    named_keys.insert(MAX_CAP.to_string(), storage::new_uref(max_cap).into());
+    named_keys.insert(MIN_STAKE.to_string(), storage::new_uref(min_stake).into());
+    named_keys.insert(MAX_STAKE.to_string(), storage::new_uref(max_stake).into());
+    named_keys.insert(
+        LOCK_PERIOD.to_string(),
+        storage::new_uref(lock_period).into(),
+    );
+    named_keys.insert(
+        DEPOSIT_START_TIME.to_string(),
+        storage::new_uref(deposit_start_time).into(),
+    );
+    named_keys.insert(
+        DEPOSIT_END_TIME.to_string(),
+        storage::new_uref(deposit_end_time).into(),
+    );
+    named_keys.insert(NOTIFIED.to_string(), storage::new_uref(false).into());
+    named_keys.insert(OWNER.to_string(), storage::new_uref(owner).into());
+    named_keys.insert(
+        REQUIRE_EXACT_TRANSFER.to_string(),
+        storage::new_uref(require_exact_transfer).into(),
+    );
+    named_keys.insert(
+        TRANSFERABLE.to_string(),
+        storage::new_uref(transferable).into(),
+    );
+    named_keys.insert(
+        RECEIPT_TOKEN.to_string(),
+        storage::new_uref(receipt_token).into(),
+    );
+    named_keys.insert(
+        RECEIPT_TOKEN_VERSION.to_string(),
+        storage::new_uref(receipt_token_version).into(),
+    );
+    named_keys.insert(
+        POSITION_NFT.to_string(),
+        storage::new_uref(position_nft).into(),
+    );
+    named_keys.insert(
+        VESTING_PERIOD.to_string(),
+        storage::new_uref(vesting_period).into(),
+    );
+    named_keys.insert(
+        VESTING_CLIFF.to_string(),
+        storage::new_uref(vesting_cliff).into(),
+    );
+    named_keys.insert(
+        INTERIM_CLAIMS.to_string(),
+        storage::new_uref(interim_claims).into(),
+    );
+    named_keys.insert(
+        UNBONDING_PERIOD.to_string(),
+        storage::new_uref(unbonding_period).into(),
+    );
+    named_keys.insert(
+        ALLOWLIST_ENABLED.to_string(),
+        storage::new_uref(allowlist_enabled).into(),
+    );
+    named_keys.insert(
+        MERKLE_ROOT.to_string(),
+        storage::new_uref(None::<[u8; 32]>).into(),
+    );
+    named_keys.insert(
+        VOUCHER_SIGNER.to_string(),
+        storage::new_uref(None::<PublicKey>).into(),
+    );
+    named_keys.insert(
+        REFERRAL_BPS.to_string(),
+        storage::new_uref(referral_bps).into(),
+    );
+    named_keys.insert(FEE_BPS.to_string(), storage::new_uref(fee_bps).into());
+    named_keys.insert(
+        DEPOSIT_FEE_BPS.to_string(),
+        storage::new_uref(deposit_fee_bps).into(),
+    );
+    named_keys.insert(
+        WITHDRAWAL_FEE_BPS.to_string(),
+        storage::new_uref(withdrawal_fee_bps).into(),
+    );
+    named_keys.insert(
+        FEE_RECIPIENT.to_string(),
+        storage::new_uref(fee_recipient).into(),
+    );
+    named_keys.insert(
+        TOTAL_FEES.to_string(),
+        storage::new_uref(U256::zero()).into(),
+    );
+    named_keys.insert(
+        TOTAL_DEPOSIT_FEES.to_string(),
+        storage::new_uref(U256::zero()).into(),
+    );
+    named_keys.insert(
+        TOTAL_WITHDRAWAL_FEES.to_string(),
+        storage::new_uref(U256::zero()).into(),
+    );
+    named_keys.insert(REENTRANCY_LOCK.to_string(), storage::new_uref(false).into());
+
+    let notify_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_NOTIFY,
+        vec![],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let stake_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_STAKE,
+        vec![
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(PROOF, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(STAKE_CAP, CLType::U256),
+            Parameter::new(VOUCHER_BOOST_BPS, CLType::U64),
+            Parameter::new(VOUCHER_EXPIRY, CLType::U64),
+            Parameter::new(VOUCHER_NONCE, CLType::U64),
+            Parameter::new(VOUCHER_SIGNATURE, CLType::List(Box::new(CLType::U8))),
+            Parameter::new(REFERRER, CLType::Key),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let stake_for_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_STAKE_FOR,
+        vec![
+            Parameter::new(BENEFICIARY, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(PROOF, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(STAKE_CAP, CLType::U256),
+            Parameter::new(VOUCHER_BOOST_BPS, CLType::U64),
+            Parameter::new(VOUCHER_EXPIRY, CLType::U64),
+            Parameter::new(VOUCHER_NONCE, CLType::U64),
+            Parameter::new(VOUCHER_SIGNATURE, CLType::List(Box::new(CLType::U8))),
+            Parameter::new(REFERRER, CLType::Key),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let unstake_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_UNSTAKE,
+        vec![
+            Parameter::new(RECIPIENT, CLType::Key),
+            Parameter::new(STAKER, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(TOKEN_ID, CLType::U64),
+        ],
+        URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let stake_entry_point: EntryPoint = EntryPoint::new(
-        ENTRY_POINT_STAKE,
-        vec![Parameter::new(AMOUNT, CLType::U256)],
+    let request_unstake_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_REQUEST_UNSTAKE,
+        vec![
+            Parameter::new(STAKER, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(TOKEN_ID, CLType::U64),
+        ],
         URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
     );
 
-    let unstake_entry_point: EntryPoint = EntryPoint::new(
-        ENTRY_POINT_UNSTAKE,
-        vec![],
+    let withdraw_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_WITHDRAW,
+        vec![Parameter::new(RECIPIENT, CLType::Key)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let exit_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_EXIT,
+        vec![
+            Parameter::new(RECIPIENT, CLType::Key),
+            Parameter::new(TOKEN_ID, CLType::U64),
+        ],
         URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -374,7 +1700,53 @@ pub extern "C" fn call() {
 
     let claim_entry_point: EntryPoint = EntryPoint::new(
         ENTRY_POINT_CLAIM,
-        vec![],
+        vec![
+            Parameter::new(RECIPIENT, CLType::Key),
+            Parameter::new(STAKER, CLType::Key),
+            Parameter::new(TOKEN_ID, CLType::U64),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let compound_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_COMPOUND,
+        vec![
+            Parameter::new(STAKER, CLType::Key),
+            Parameter::new(TOKEN_ID, CLType::U64),
+            Parameter::new(PROOF, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(STAKE_CAP, CLType::U256),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let approve_operator_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_APPROVE_OPERATOR,
+        vec![Parameter::new(OPERATOR, CLType::Key)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let revoke_operator_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_REVOKE_OPERATOR,
+        vec![Parameter::new(OPERATOR, CLType::Key)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let transfer_position_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_TRANSFER_POSITION,
+        vec![
+            Parameter::new(TO, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(PROOF, CLType::List(Box::new(CLType::ByteArray(32)))),
+            Parameter::new(STAKE_CAP, CLType::U256),
+        ],
         URef,
         EntryPointAccess::Public,
         EntryPointType::Contract,
@@ -388,13 +1760,127 @@ pub extern "C" fn call() {
         EntryPointType::Contract,
     );
 
+    let add_rewards_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_ADD_REWARDS,
+        vec![
+            Parameter::new(AMOUNT, CLType::U256),
+            Parameter::new(APR, CLType::U64),
+            Parameter::new(LOCK_PERIOD, CLType::U64),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let set_max_cap_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_SET_MAX_CAP,
+        vec![Parameter::new(MAX_CAP, CLType::U256)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let set_stake_limits_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_SET_STAKE_LIMITS,
+        vec![
+            Parameter::new(MIN_STAKE, CLType::U256),
+            Parameter::new(MAX_STAKE, CLType::U256),
+        ],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let extend_deposit_window_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_EXTEND_DEPOSIT_WINDOW,
+        vec![Parameter::new(DEPOSIT_END_TIME, CLType::U64)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let add_to_allowlist_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_ADD_TO_ALLOWLIST,
+        vec![Parameter::new(
+            ADDRESSES,
+            CLType::List(Box::new(CLType::Key)),
+        )],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let remove_from_allowlist_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_REMOVE_FROM_ALLOWLIST,
+        vec![Parameter::new(
+            ADDRESSES,
+            CLType::List(Box::new(CLType::Key)),
+        )],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let set_merkle_root_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_SET_MERKLE_ROOT,
+        vec![Parameter::new(MERKLE_ROOT, CLType::ByteArray(32))],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let set_voucher_signer_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_SET_VOUCHER_SIGNER,
+        vec![Parameter::new(
+            VOUCHER_SIGNER,
+            CLType::Option(Box::new(CLType::PublicKey)),
+        )],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let claim_referral_rewards_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_CLAIM_REFERRAL_REWARDS,
+        vec![Parameter::new(RECIPIENT, CLType::Key)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
+    let set_fee_bps_entry_point: EntryPoint = EntryPoint::new(
+        ENTRY_POINT_SET_FEE_BPS,
+        vec![Parameter::new(FEE_BPS, CLType::U64)],
+        URef,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    );
+
     let mut entry_points: EntryPoints = EntryPoints::new();
 
     entry_points.add_entry_point(notify_entry_point);
     entry_points.add_entry_point(stake_entry_point);
+    entry_points.add_entry_point(stake_for_entry_point);
     entry_points.add_entry_point(unstake_entry_point);
+    entry_points.add_entry_point(request_unstake_entry_point);
+    entry_points.add_entry_point(withdraw_entry_point);
+    entry_points.add_entry_point(exit_entry_point);
     entry_points.add_entry_point(claim_entry_point);
+    entry_points.add_entry_point(compound_entry_point);
+    entry_points.add_entry_point(approve_operator_entry_point);
+    entry_points.add_entry_point(revoke_operator_entry_point);
+    entry_points.add_entry_point(transfer_position_entry_point);
     entry_points.add_entry_point(refund_reward_entry_point);
+    entry_points.add_entry_point(add_rewards_entry_point);
+    entry_points.add_entry_point(set_max_cap_entry_point);
+    entry_points.add_entry_point(set_stake_limits_entry_point);
+    entry_points.add_entry_point(extend_deposit_window_entry_point);
+    entry_points.add_entry_point(add_to_allowlist_entry_point);
+    entry_points.add_entry_point(remove_from_allowlist_entry_point);
+    entry_points.add_entry_point(set_merkle_root_entry_point);
+    entry_points.add_entry_point(set_voucher_signer_entry_point);
+    entry_points.add_entry_point(claim_referral_rewards_entry_point);
+    entry_points.add_entry_point(set_fee_bps_entry_point);
 
     let ph_text: String = String::from("stake_package_hash_");
     let ch_text: String = String::from("stake_contract_hash_");
@@ -422,6 +1908,27 @@ pub extern "C" fn call() {
     );
 }
 
+/// Recomputes the effective APR of a position, in basis points, from its
+/// reward entitlement and staked balance.
+pub fn update_position_apr(staker_item_key: &str) {
+    let staked: U256 =
+        utils::dictionary_read(STAKES_BALANCE_DICT, staker_item_key).unwrap_or_default();
+    let reward: U256 = utils::dictionary_read(REWARDS_DICT, staker_item_key).unwrap_or_default();
+    let apr: u64 = if staked.is_zero() {
+        0
+    } else {
+        reward
+            .checked_mul(U256::from(10_000))
+            .unwrap_or_revert_with(Error::Overflow)
+            .div(staked)
+            .try_into()
+            .ok()
+            .unwrap_or_revert_with(Error::Overflow)
+    };
+
+    utils::dictionary_write(STAKES_APR_DICT, staker_item_key, apr);
+}
+
 pub fn only_owner() {
     let admin: AccountHash = utils::get_key(OWNER);
     let caller: AccountHash = runtime::get_caller();
@@ -430,18 +1937,536 @@ pub fn only_owner() {
     }
 }
 
+/// Reads the optional `recipient` argument, falling back to `default`. Only
+/// account and contract keys are accepted.
+pub fn recipient_or(default: Key) -> Key {
+    let recipient: Key = utils::get_optional_named_arg(RECIPIENT).unwrap_or(default);
+
+    utils::validate_key(&recipient);
+
+    recipient
+}
+
+/// Client for the staked token, speaking the CEP-18 generation chosen at install.
+pub fn token_contract() -> CEP18 {
+    let token: Key = utils::read_from(TOKEN);
+    let token_version: u8 = utils::read_from(TOKEN_VERSION);
+    let version = Cep18Version::try_from(token_version)
+        .ok()
+        .unwrap_or_revert_with(Error::InvalidTokenVersion);
+
+    CEP18::new(
+        token
+            .into_hash()
+            .map(ContractHash::new)
+            .unwrap_or_revert_with(Error::InvalidKey),
+        version,
+    )
+}
+
+/// The optional receipt token minted 1:1 for staked principal. The pool has to
+/// be on its minter list. Receipts are the claim on the principal, whoever
+/// holds a position's worth of them can unstake it.
+pub fn receipt_contract() -> Option<CEP18> {
+    let receipt_token: Option<Key> = utils::read_from(RECEIPT_TOKEN);
+
+    receipt_token.map(|receipt_token| {
+        let receipt_token_version: u8 = utils::read_from(RECEIPT_TOKEN_VERSION);
+        let version = Cep18Version::try_from(receipt_token_version)
+            .ok()
+            .unwrap_or_revert_with(Error::InvalidTokenVersion);
+
+        CEP18::new(
+            receipt_token
+                .into_hash()
+                .map(ContractHash::new)
+                .unwrap_or_revert_with(Error::InvalidKey),
+            version,
+        )
+    })
+}
+
+/// The position the caller unstakes and how much of it. With a receipt token
+/// it is the one of the optional `staker` argument, redeemed one for one with
+/// the caller's receipts for the optional `amount`, while its rewards stay
+/// with the staker. Otherwise the whole position is unstaked.
+pub fn redeemed_position(caller: Address) -> (Key, Option<U256>) {
+    if receipt_contract().is_none() {
+        return (position_of(caller), None);
+    }
+
+    (
+        utils::get_optional_named_arg(STAKER).unwrap_or_else(|| caller.into()),
+        utils::get_optional_named_arg(AMOUNT),
+    )
+}
+
+/// The optional CEP-78 collection positions are minted into. The pool has to be
+/// allowed to mint, and metadata has to be mutable so top-ups can update it.
+pub fn position_nft_contract() -> Option<CEP78> {
+    let position_nft: Option<Key> = utils::read_from(POSITION_NFT);
+
+    position_nft.map(|position_nft| {
+        CEP78::new(
+            position_nft
+                .into_hash()
+                .map(ContractHash::new)
+                .unwrap_or_revert_with(Error::InvalidKey),
+        )
+    })
+}
+
+/// The position the caller acts on. With position NFTs it is the one behind
+/// the `token_id` argument, which the caller has to hold itself, operators
+/// cannot unstake.
+pub fn position_of(caller: Address) -> Key {
+    let nft: CEP78 = match position_nft_contract() {
+        Some(nft) => nft,
+        None => return caller.into(),
+    };
+    let (position, holder): (Key, Key) = tokenized_position(&nft);
+
+    if holder != caller.into() {
+        runtime::revert(Error::NotPositionOwner);
+    }
+
+    position
+}
+
+/// The position behind the `token_id` argument and the current holder of its
+/// token.
+pub fn tokenized_position(nft: &CEP78) -> (Key, Key) {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+    let position: Key = utils::dictionary_read(TOKEN_POSITIONS_DICT, &token_id.to_string())
+        .unwrap_or_revert_with(Error::NotPositionOwner);
+
+    (position, nft.owner_of(token_id))
+}
+
+/// Token id of the position NFT minted for a position, if any.
+pub fn position_token_of(staker_item_key: &str) -> Option<u64> {
+    utils::dictionary_read::<Option<u64>>(POSITION_TOKENS_DICT, staker_item_key).flatten()
+}
+
+/// Once a staker's position token is held by someone else the position belongs
+/// to that holder. It is moved off the staker's key onto a key derived from the
+/// token, so the staker can open a new position with a new token.
+pub fn detach_sold_position(nft: &CEP78, staker: Key, staker_item_key: &str) {
+    let token_id: u64 = match position_token_of(staker_item_key) {
+        Some(token_id) => token_id,
+        None => return,
+    };
+
+    if nft.owner_of(token_id) == staker {
+        return;
+    }
+
+    let mut preimage: Vec<u8> = b"position".to_vec();
+    preimage.append(&mut token_id.to_bytes().unwrap_or_revert());
+
+    let position: Key = Key::Hash(utils::sha3_256(&preimage));
+    let position_item_key: String = utils::encode_dictionary_item_key(position);
+
+    for dictionary_name in [STAKES_DICT, STAKES_BALANCE_DICT, REWARDS_DICT, CLAIMED_DICT] {
+        let value: U256 =
+            utils::dictionary_read(dictionary_name, staker_item_key).unwrap_or_default();
+
+        utils::dictionary_write(dictionary_name, &position_item_key, value);
+        utils::dictionary_write(dictionary_name, staker_item_key, U256::zero());
+    }
+
+    for dictionary_name in [STAKES_APR_DICT, LAST_CLAIM_TIME_DICT, UNLOCK_TIME_DICT] {
+        let value: u64 =
+            utils::dictionary_read(dictionary_name, staker_item_key).unwrap_or_default();
+
+        utils::dictionary_write(dictionary_name, &position_item_key, value);
+        utils::dictionary_write(dictionary_name, staker_item_key, 0u64);
+    }
+
+    utils::dictionary_write(TOKEN_POSITIONS_DICT, &token_id.to_string(), position);
+    utils::dictionary_write(POSITION_TOKENS_DICT, &position_item_key, Some(token_id));
+    utils::dictionary_write(POSITION_TOKENS_DICT, staker_item_key, None::<u64>);
+}
+
+/// NFT metadata of a position: principal, lock end and APR in basis points.
+pub fn position_metadata(staker_item_key: &str) -> String {
+    let amount: U256 = utils::dictionary_read(STAKES_DICT, staker_item_key).unwrap_or_default();
+    let apr: u64 = utils::dictionary_read(STAKES_APR_DICT, staker_item_key).unwrap_or_default();
+
+    format!(
+        r#"{{"amount":"{}","lock_end":"{}","apr":"{}"}}"#,
+        amount,
+        unlock_time_of(staker_item_key),
+        apr
+    )
+}
+
+/// Rewrites the metadata of the staker's position NFT, if it has one.
+pub fn refresh_position_metadata(staker_item_key: &str) {
+    if let Some(nft) = position_nft_contract() {
+        if let Some(token_id) = position_token_of(staker_item_key) {
+            nft.set_token_metadata(token_id, position_metadata(staker_item_key));
+        }
+    }
+}
+
+/// Reverts unless `owner` has allowed the pool to pull `amount`.
+pub fn ensure_allowance(cep18: &CEP18, owner: Key, amount: U256) {
+    let contract_address: Key = get_current_address().into();
+    let allowance: U256 = cep18.allowance(owner, contract_address);
+
+    if allowance.lt(&amount) {
+        runtime::revert(Error::InsufficientAllowance);
+    }
+}
+
+/// Pulls `amount` from `owner` into the pool and returns how much actually
+/// arrived, so fee-on-transfer tokens are never over-credited. Callers write
+/// their state for `amount` first and correct it if less arrived.
+pub fn receive_tokens(cep18: &CEP18, owner: Key, amount: U256) -> U256 {
+    let contract_address: Key = get_current_address().into();
+    let balance_before: U256 = cep18.balance_of(contract_address);
+
+    cep18.transfer_from(owner, contract_address, amount);
+
+    let balance_after: U256 = cep18.balance_of(contract_address);
+    let received: U256 = balance_after
+        .checked_sub(balance_before)
+        .unwrap_or_revert_with(Error::Underflow);
+
+    if received.lt(&amount) {
+        let require_exact_transfer: bool = utils::read_from(REQUIRE_EXACT_TRANSFER);
+
+        if require_exact_transfer || received.is_zero() {
+            runtime::revert(Error::TransferAmountMismatch);
+        }
+
+        return received;
+    }
+
+    amount
+}
+
+/// Rejects any call into the contract while another entry point is still
+/// running. Every entry point that changes state holds the lock, so none of
+/// them can be re-entered from a token, receipt or NFT contract call.
+pub fn reentrancy_guard_enter() {
+    let locked: bool = utils::read_from(REENTRANCY_LOCK);
+
+    if locked {
+        runtime::revert(Error::ReentrantCall);
+    }
+
+    runtime::put_key(REENTRANCY_LOCK, storage::new_uref(true).into());
+}
+
+pub fn reentrancy_guard_exit() {
+    runtime::put_key(REENTRANCY_LOCK, storage::new_uref(false).into());
+}
+
 pub fn calculate_dynamic_apr(total_supply: U256, max_cap: U256, min_apr: u64, max_apr: u64) -> u64 {
-    let ratio = (total_supply * U256::from(100u64)) / max_cap;
+    let ratio = total_supply
+        .checked_mul(U256::from(100u64))
+        .unwrap_or_revert_with(Error::Overflow)
+        .div(max_cap);
 
-    let ratio_u64 = ratio.as_u64();
+    if ratio >= U256::from(100u64) {
+        return min_apr;
+    }
 
-    let dynamic_apr = if ratio_u64 >= 100 {
-        min_apr
-    } else {
-        let scaled_ratio = ((max_apr - min_apr) * (100 - ratio_u64)) / 100;
+    let ratio_u64: u64 = ratio.try_into().ok().unwrap_or_revert_with(Error::Overflow);
+    let apr_range = max_apr
+        .checked_sub(min_apr)
+        .unwrap_or_revert_with(Error::Underflow);
+    let scaled_ratio = apr_range
+        .checked_mul(100 - ratio_u64)
+        .unwrap_or_revert_with(Error::Overflow)
+        / 100;
+
+    min_apr
+        .checked_add(scaled_ratio)
+        .unwrap_or_revert_with(Error::Overflow)
+}
+
+/// Time at which the lock of a deposit made now would end. Lock periods and the
+/// deposit window only ever grow, so no position unlocks later than this.
+pub fn lock_expire_time() -> u64 {
+    let deposit_end_time: u64 = utils::read_from(DEPOSIT_END_TIME);
+    let lock_period: u64 = utils::read_from(LOCK_PERIOD);
+
+    deposit_end_time
+        .checked_add(lock_period)
+        .unwrap_or_revert_with(Error::Overflow)
+}
+
+/// Time at which the staker's position unlocks. It is fixed by the last
+/// deposit into the position, so later changes to the lock period or the
+/// deposit window never move it.
+pub fn unlock_time_of(staker_item_key: &str) -> u64 {
+    utils::dictionary_read(UNLOCK_TIME_DICT, staker_item_key).unwrap_or_else(lock_expire_time)
+}
+
+/// Part of `reward` released at `now` for a position unlocking at
+/// `expire_time`. Rewards vest linearly over the vesting period starting when
+/// the lock ends, nothing is released before the cliff. Without a vesting
+/// period everything is released when the lock ends.
+pub fn vested_amount(reward: U256, expire_time: u64, now: u64) -> U256 {
+    let vesting_period: u64 = utils::read_from(VESTING_PERIOD);
+
+    if now < expire_time {
+        return U256::zero();
+    }
+
+    let elapsed: u64 = now - expire_time;
+
+    if vesting_period == 0 || elapsed >= vesting_period {
+        return reward;
+    }
+
+    let vesting_cliff: u64 = utils::read_from(VESTING_CLIFF);
+
+    if elapsed < vesting_cliff {
+        return U256::zero();
+    }
+
+    reward
+        .checked_mul(U256::from(elapsed))
+        .unwrap_or_revert_with(Error::Overflow)
+        .div(U256::from(vesting_period))
+}
+
+/// Share of the `unclaimed` rewards accrued since the staker's last claim, and
+/// moves the staker's claim cursor to `now`. Rewards accrue linearly over the
+/// lock, from the end of the deposit window until the position unlocks, so
+/// the remainder is always fully paid out by the lock end.
+pub fn accrued_since_last_claim(staker_item_key: &str, unclaimed: U256, now: u64) -> U256 {
+    let expire_time: u64 = unlock_time_of(staker_item_key);
+    let lock_start: u64 = utils::read_from::<u64>(DEPOSIT_END_TIME).min(expire_time);
+    let last_claim_time: u64 = utils::dictionary_read::<u64>(LAST_CLAIM_TIME_DICT, staker_item_key)
+        .unwrap_or_default()
+        .max(lock_start);
+
+    if now <= last_claim_time {
+        return U256::zero();
+    }
+
+    utils::dictionary_write(LAST_CLAIM_TIME_DICT, staker_item_key, now);
+
+    if now >= expire_time {
+        return unclaimed;
+    }
+
+    unclaimed
+        .checked_mul(U256::from(now - last_claim_time))
+        .unwrap_or_revert_with(Error::Overflow)
+        .div(U256::from(expire_time - last_claim_time))
+}
+
+/// Reverts unless allowlist mode is off or the staker is on the allowlist.
+pub fn ensure_allowlisted(staker_item_key: &str) {
+    let allowlist_enabled: bool = utils::read_from(ALLOWLIST_ENABLED);
+
+    if !allowlist_enabled {
+        return;
+    }
+
+    let allowed: bool = utils::dictionary_read(ALLOWLIST_DICT, staker_item_key).unwrap_or_default();
+
+    if !allowed {
+        runtime::revert(Error::NotWhitelisted);
+    }
+}
 
-        min_apr + scaled_ratio
+/// Largest position `staker` may hold. Once a Merkle root is set the staker
+/// has to prove its leaf, the tagged hash of its key and `stake_cap` from
+/// `utils::merkle_leaf`, through the `proof` argument, and that cap replaces
+/// `max_stake`.
+pub fn max_stake_of(staker: Key) -> U256 {
+    let merkle_root: Option<[u8; 32]> = utils::read_from(MERKLE_ROOT);
+    let merkle_root: [u8; 32] = match merkle_root {
+        Some(merkle_root) => merkle_root,
+        None => return utils::read_from(MAX_STAKE),
     };
+    let stake_cap: U256 =
+        utils::get_optional_named_arg(STAKE_CAP).unwrap_or_revert_with(Error::InvalidMerkleProof);
+    let proof: Vec<[u8; 32]> = utils::get_optional_named_arg(PROOF).unwrap_or_default();
 
-    dynamic_apr
+    let mut preimage: Vec<u8> = staker.to_bytes().unwrap_or_revert();
+    preimage.append(&mut stake_cap.to_bytes().unwrap_or_revert());
+
+    if !utils::verify_merkle_proof(utils::merkle_leaf(&preimage), &proof, merkle_root) {
+        runtime::revert(Error::InvalidMerkleProof);
+    }
+
+    stake_cap
+}
+
+/// Boost in basis points granted by the optional voucher arguments. A voucher
+/// is the voucher signer's signature over the hash of this contract, the
+/// staker, the boost, the expiry and a nonce, and can be redeemed only once,
+/// by the staker itself.
+pub fn redeem_voucher(payer: Key, staker: Key) -> u64 {
+    let signature: Bytes = match utils::get_optional_named_arg(VOUCHER_SIGNATURE) {
+        Some(signature) => signature,
+        None => return 0,
+    };
+
+    // otherwise anyone could burn a staker's voucher on a dust deposit
+    if payer != staker {
+        runtime::revert(Error::InvalidVoucher);
+    }
+
+    let boost_bps: u64 = utils::get_optional_named_arg(VOUCHER_BOOST_BPS)
+        .unwrap_or_revert_with(Error::InvalidVoucher);
+    let expiry: u64 =
+        utils::get_optional_named_arg(VOUCHER_EXPIRY).unwrap_or_revert_with(Error::InvalidVoucher);
+    let nonce: u64 =
+        utils::get_optional_named_arg(VOUCHER_NONCE).unwrap_or_revert_with(Error::InvalidVoucher);
+    let voucher_signer: Option<PublicKey> = utils::read_from(VOUCHER_SIGNER);
+    let voucher_signer: PublicKey = voucher_signer.unwrap_or_revert_with(Error::InvalidVoucher);
+    let now: u64 = runtime::get_blocktime().into();
+
+    if expiry < now {
+        runtime::revert(Error::VoucherExpired);
+    }
+
+    let nonce_item_key: String = nonce.to_string();
+    let used: bool =
+        utils::dictionary_read(VOUCHER_NONCES_DICT, &nonce_item_key).unwrap_or_default();
+
+    if used {
+        runtime::revert(Error::VoucherAlreadyUsed);
+    }
+
+    let package: Key = get_current_address().into();
+    let mut preimage: Vec<u8> = package.to_bytes().unwrap_or_revert();
+    preimage.append(&mut staker.to_bytes().unwrap_or_revert());
+    preimage.append(&mut boost_bps.to_bytes().unwrap_or_revert());
+    preimage.append(&mut expiry.to_bytes().unwrap_or_revert());
+    preimage.append(&mut nonce.to_bytes().unwrap_or_revert());
+
+    let (signature, _) =
+        Signature::from_bytes(&signature).unwrap_or_revert_with(Error::InvalidVoucher);
+
+    if crypto::verify(utils::sha3_256(&preimage), &signature, &voucher_signer).is_err() {
+        runtime::revert(Error::InvalidVoucher);
+    }
+
+    utils::dictionary_write(VOUCHER_NONCES_DICT, &nonce_item_key, true);
+
+    boost_bps
+}
+
+/// The staker's referrer, who earns a share of every reward the staker is
+/// granted. The optional `referrer` argument is recorded the first time a
+/// staker names one, which only the staker itself can do.
+pub fn referrer_of(payer: Key, staker: Key, staker_item_key: &str) -> Option<Key> {
+    if let Some(referrer) = utils::dictionary_read(REFERRERS_DICT, staker_item_key) {
+        return Some(referrer);
+    }
+
+    let referrer: Option<Key> = utils::get_optional_named_arg(REFERRER);
+
+    if let Some(referrer) = referrer {
+        if referrer == staker || payer != staker {
+            runtime::revert(Error::InvalidReferrer);
+        }
+
+        utils::validate_key(&referrer);
+        utils::dictionary_write(REFERRERS_DICT, staker_item_key, referrer);
+    }
+
+    referrer
+}
+
+/// Adds the referral bonus that may be owed on top of `reward`.
+pub fn with_referral_share(reward: U256) -> U256 {
+    let referral_bps: u64 = utils::read_from(REFERRAL_BPS);
+
+    reward
+        .checked_add(bps_of(reward, referral_bps))
+        .unwrap_or_revert_with(Error::Overflow)
+}
+
+/// `bps` basis points of `amount`, rounded down.
+pub fn bps_of(amount: U256, bps: u64) -> U256 {
+    amount
+        .checked_mul(U256::from(bps))
+        .unwrap_or_revert_with(Error::Overflow)
+        .div(U256::from(10_000))
+}
+
+/// Fee owed on `amount` at the rate stored under `fee_bps_name`. It is added to
+/// the running total under `total_fees_name` here and sent with `pay_fee` once
+/// the pool's own state is updated.
+pub fn take_fee(amount: U256, fee_bps_name: &str, total_fees_name: &str) -> U256 {
+    let fee_bps: u64 = utils::read_from(fee_bps_name);
+
+    if fee_bps == 0 {
+        return U256::zero();
+    }
+
+    let fee: U256 = bps_of(amount, fee_bps);
+    let total_fees: U256 = utils::read_from(total_fees_name);
+
+    runtime::put_key(
+        total_fees_name,
+        storage::new_uref(
+            total_fees
+                .checked_add(fee)
+                .unwrap_or_revert_with(Error::Overflow),
+        )
+        .into(),
+    );
+
+    fee
+}
+
+pub fn pay_fee(cep18: &CEP18, fee: U256) {
+    if fee.is_zero() {
+        return;
+    }
+
+    let fee_recipient: Key = utils::read_from(FEE_RECIPIENT);
+
+    cep18.transfer(fee_recipient, fee);
+}
+
+pub fn validate_stake_limits(min_stake: U256, max_stake: U256, max_cap: U256) {
+    if min_stake.gt(&max_stake) || max_stake.gt(&max_cap) {
+        runtime::revert(Error::InvalidStakeLimits);
+    }
+}
+
+/// Highest APR a future deposit can still lock in.
+pub fn ceiling_apr() -> u64 {
+    let fixed_apr: u64 = utils::read_from(FIXED_APR);
+
+    if fixed_apr > 0 {
+        fixed_apr
+    } else {
+        utils::read_from(MAX_APR)
+    }
+}
+
+/// Reverts unless the funded rewards cover what is already promised plus the
+/// remaining capacity deposited at `apr`.
+pub fn ensure_rewards_cover_capacity(total_reward: U256, max_cap: U256, apr: u64) {
+    let total_supply: U256 = utils::read_from(TOTAL_SUPPLY);
+    let total_liability: U256 = utils::read_from(TOTAL_LIABILITY);
+
+    let remaining_capacity = max_cap.saturating_sub(total_supply);
+    let remaining_reward = with_referral_share(
+        remaining_capacity
+            .checked_mul(U256::from(apr))
+            .unwrap_or_revert_with(Error::Overflow)
+            .div(U256::from(100)),
+    );
+    let required = total_liability
+        .checked_add(remaining_reward)
+        .unwrap_or_revert_with(Error::Overflow);
+
+    if required.gt(&total_reward) {
+        runtime::revert(Error::InsufficientRewardFunds);
+    }
 }
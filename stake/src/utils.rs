@@ -2,14 +2,24 @@
 
 use crate::enums::Address;
 use crate::error::Error;
-use alloc::string::{String, ToString};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
 use casper_contract::{
-    contract_api::runtime, contract_api::storage, unwrap_or_revert::UnwrapOrRevert,
+    contract_api::{self, runtime, storage},
+    ext_ffi,
+    unwrap_or_revert::UnwrapOrRevert,
 };
 use casper_types::{
-    bytesrepr::FromBytes, system::CallStackElement, CLTyped, ContractHash, Key, URef,
+    api_error,
+    bytesrepr::{self, FromBytes, ToBytes},
+    system::CallStackElement,
+    ApiError, CLTyped, ContractHash, Key, URef,
 };
 use core::convert::TryInto;
+use tiny_keccak::{Hasher, Sha3};
 
 pub fn get_key<T: FromBytes + CLTyped>(name: &str) -> T {
     let key = runtime::get_key(name)
@@ -30,6 +40,24 @@ where
     value
 }
 
+/// Reads `item_key` from the dictionary stored under `dictionary_name`.
+pub(crate) fn dictionary_read<T>(dictionary_name: &str, item_key: &str) -> Option<T>
+where
+    T: FromBytes + CLTyped,
+{
+    let dictionary = get_uref(dictionary_name);
+    storage::dictionary_get::<T>(dictionary, item_key).unwrap_or_revert()
+}
+
+/// Writes `value` under `item_key` into the dictionary stored under `dictionary_name`.
+pub(crate) fn dictionary_write<T>(dictionary_name: &str, item_key: &str, value: T)
+where
+    T: ToBytes + CLTyped,
+{
+    let dictionary = get_uref(dictionary_name);
+    storage::dictionary_put(dictionary, item_key, value);
+}
+
 /// Gets [`URef`] under a name.
 pub(crate) fn get_uref(name: &str) -> URef {
     let key = runtime::get_key(name)
@@ -45,6 +73,21 @@ pub fn get_current_address() -> Address {
         .rev()
         .next()
         .unwrap_or_revert();
+    call_stack_element_to_address(call_stack_element)
+}
+
+/// Returns the account or contract package that called into this contract,
+/// unlike `runtime::get_caller` which always yields the deploy's signer.
+pub fn get_immediate_caller_address() -> Address {
+    let call_stack_element = runtime::get_call_stack()
+        .into_iter()
+        .rev()
+        .nth(1)
+        .unwrap_or_revert_with(Error::FatalError);
+    call_stack_element_to_address(call_stack_element)
+}
+
+fn call_stack_element_to_address(call_stack_element: CallStackElement) -> Address {
     match call_stack_element {
         CallStackElement::Session { account_hash } => Address::from(account_hash),
         CallStackElement::StoredSession { account_hash, .. } => Address::from(account_hash),
@@ -55,6 +98,14 @@ pub fn get_current_address() -> Address {
     }
 }
 
+/// Reverts with `InvalidKey` unless `key` is an account or a contract.
+pub fn validate_key(key: &Key) {
+    match key {
+        Key::Account(_) | Key::Hash(_) => {}
+        _ => runtime::revert(Error::InvalidKey),
+    }
+}
+
 // ref => https://github.com/casper-ecosystem/cep-78-enhanced-nft/blob/dev/contract/src/utils.rs#L83
 pub fn encode_dictionary_item_key(key: Key) -> String {
     match key {
@@ -63,3 +114,103 @@ pub fn encode_dictionary_item_key(key: Key) -> String {
         _ => runtime::revert(Error::InvalidKey),
     }
 }
+
+/// Item key for a `(first, second)` pair of keys. Both keys together exceed the
+/// dictionary item key length, so their hash is used instead.
+pub fn encode_dictionary_pair_key(first: Key, second: Key) -> String {
+    let mut preimage: Vec<u8> = Vec::new();
+    preimage.append(&mut first.to_bytes().unwrap_or_revert());
+    preimage.append(&mut second.to_bytes().unwrap_or_revert());
+
+    encode_hex(&sha3_256(&preimage))
+}
+
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+// prefixes hashed into Merkle leaves and inner nodes, so a leaf can never be
+// passed off as a pair of nodes
+const MERKLE_LEAF_TAG: u8 = 0x00;
+const MERKLE_NODE_TAG: u8 = 0x01;
+
+/// Hash of a Merkle leaf holding `data`.
+pub fn merkle_leaf(data: &[u8]) -> [u8; 32] {
+    let mut preimage: Vec<u8> = Vec::with_capacity(data.len() + 1);
+    preimage.push(MERKLE_LEAF_TAG);
+    preimage.extend_from_slice(data);
+
+    sha3_256(&preimage)
+}
+
+/// Checks `proof` leads from `leaf`, as returned by `merkle_leaf`, to `root`.
+/// Each pair of nodes is hashed in ascending order, so proofs carry no
+/// left/right flags.
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let computed: [u8; 32] = proof.iter().fold(leaf, |node, sibling| {
+        let mut pair: Vec<u8> = Vec::with_capacity(65);
+        pair.push(MERKLE_NODE_TAG);
+
+        if node <= *sibling {
+            pair.extend_from_slice(&node);
+            pair.extend_from_slice(sibling);
+        } else {
+            pair.extend_from_slice(sibling);
+            pair.extend_from_slice(&node);
+        }
+
+        sha3_256(&pair)
+    });
+
+    computed == root
+}
+
+/// Lower-case hex encoding of `bytes`.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+// ref => https://github.com/casper-ecosystem/cep18/blob/dev/cep18/src/utils.rs
+/// Reads a named argument that callers are allowed to omit.
+pub(crate) fn get_optional_named_arg<T: FromBytes>(name: &str) -> Option<T> {
+    let arg_size = get_named_arg_size(name)?;
+    let arg_bytes = if arg_size > 0 {
+        let data_non_null_ptr = contract_api::alloc_bytes(arg_size);
+        let ret = unsafe {
+            ext_ffi::casper_get_named_arg(
+                name.as_bytes().as_ptr(),
+                name.len(),
+                data_non_null_ptr.as_ptr(),
+                arg_size,
+            )
+        };
+        let data = unsafe { Vec::from_raw_parts(data_non_null_ptr.as_ptr(), arg_size, arg_size) };
+        api_error::result_from(ret)
+            .map(|_| data)
+            .unwrap_or_revert_with(Error::FatalError)
+    } else {
+        Vec::new()
+    };
+
+    Some(bytesrepr::deserialize(arg_bytes).unwrap_or_revert_with(ApiError::InvalidArgument))
+}
+
+fn get_named_arg_size(name: &str) -> Option<usize> {
+    let mut arg_size: usize = 0;
+    let ret = unsafe {
+        ext_ffi::casper_get_named_arg_size(
+            name.as_bytes().as_ptr(),
+            name.len(),
+            &mut arg_size as *mut usize,
+        )
+    };
+    match api_error::result_from(ret) {
+        Ok(_) => Some(arg_size),
+        Err(ApiError::MissingArgument) => None,
+        Err(e) => runtime::revert(e),
+    }
+}
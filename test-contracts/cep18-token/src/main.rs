@@ -0,0 +1,424 @@
+#![no_std]
+#![no_main]
+
+//! Minimal CEP-18 token for the integration tests. Anyone may mint. The
+//! `balance_of` argument name follows the installed `version`, transfers can
+//! charge a fee that is burned, and `transfer_from` can call back into a hook
+//! contract before moving any tokens.
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    contracts::NamedKeys,
+    runtime_args,
+    system::CallStackElement,
+    ApiError, CLType, CLTyped, CLValue, ContractHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, RuntimeArgs, URef, U256,
+};
+use core::convert::TryInto;
+use tiny_keccak::{Hasher, Sha3};
+
+const NAME: &str = "name";
+const SYMBOL: &str = "symbol";
+const DECIMALS: &str = "decimals";
+const TOTAL_SUPPLY: &str = "total_supply";
+const VERSION: &str = "version";
+const TRANSFER_FEE_BPS: &str = "transfer_fee_bps";
+const HOOK: &str = "hook";
+const HOOK_ENTRY_POINT: &str = "hook_entry_point";
+const BALANCES: &str = "balances";
+const ALLOWANCES: &str = "allowances";
+const OWNER: &str = "owner";
+const ADDRESS: &str = "address";
+const SPENDER: &str = "spender";
+const RECIPIENT: &str = "recipient";
+const AMOUNT: &str = "amount";
+
+const ERROR_INSUFFICIENT_BALANCE: u16 = 1;
+const ERROR_INSUFFICIENT_ALLOWANCE: u16 = 2;
+const ERROR_NOT_OWNER: u16 = 3;
+const ERROR_ALREADY_INITIALIZED: u16 = 4;
+
+#[no_mangle]
+pub extern "C" fn name() {
+    ret(read_from::<String>(NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn symbol() {
+    ret(read_from::<String>(SYMBOL));
+}
+
+#[no_mangle]
+pub extern "C" fn decimals() {
+    ret(read_from::<u8>(DECIMALS));
+}
+
+#[no_mangle]
+pub extern "C" fn total_supply() {
+    ret(read_from::<U256>(TOTAL_SUPPLY));
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let version: u8 = read_from(VERSION);
+    let owner: Key = if version == 1 {
+        runtime::get_named_arg(ADDRESS)
+    } else {
+        runtime::get_named_arg(OWNER)
+    };
+
+    ret(balance(owner));
+}
+
+#[no_mangle]
+pub extern "C" fn allowance() {
+    let owner: Key = runtime::get_named_arg(OWNER);
+    let spender: Key = runtime::get_named_arg(SPENDER);
+
+    ret(allowance_of(owner, spender));
+}
+
+#[no_mangle]
+pub extern "C" fn approve() {
+    let owner: Key = immediate_caller();
+    let spender: Key = runtime::get_named_arg(SPENDER);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+
+    storage::dictionary_put(get_uref(ALLOWANCES), &pair_key(owner, spender), amount);
+}
+
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let recipient: Key = runtime::get_named_arg(RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+
+    move_tokens(immediate_caller(), recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn transfer_from() {
+    let spender: Key = immediate_caller();
+    let owner: Key = runtime::get_named_arg(OWNER);
+    let recipient: Key = runtime::get_named_arg(RECIPIENT);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+    let allowance: U256 = allowance_of(owner, spender);
+
+    if allowance < amount {
+        runtime::revert(ApiError::User(ERROR_INSUFFICIENT_ALLOWANCE));
+    }
+
+    storage::dictionary_put(
+        get_uref(ALLOWANCES),
+        &pair_key(owner, spender),
+        allowance - amount,
+    );
+
+    let hook: Option<Key> = read_from(HOOK);
+
+    if let Some(hook) = hook {
+        let hook_entry_point: String = read_from(HOOK_ENTRY_POINT);
+
+        runtime::call_contract::<()>(
+            hook.into_hash()
+                .map(ContractHash::new)
+                .unwrap_or_revert_with(ApiError::InvalidArgument),
+            &hook_entry_point,
+            runtime_args! {},
+        );
+    }
+
+    move_tokens(owner, recipient, amount);
+}
+
+#[no_mangle]
+pub extern "C" fn mint() {
+    let owner: Key = runtime::get_named_arg(OWNER);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+    let total_supply: U256 = read_from(TOTAL_SUPPLY);
+
+    set_balance(owner, balance(owner) + amount);
+    write_to(TOTAL_SUPPLY, total_supply + amount);
+}
+
+#[no_mangle]
+pub extern "C" fn burn() {
+    let owner: Key = runtime::get_named_arg(OWNER);
+    let amount: U256 = runtime::get_named_arg(AMOUNT);
+
+    if owner != immediate_caller() {
+        runtime::revert(ApiError::User(ERROR_NOT_OWNER));
+    }
+
+    let owner_balance: U256 = balance(owner);
+
+    if owner_balance < amount {
+        runtime::revert(ApiError::User(ERROR_INSUFFICIENT_BALANCE));
+    }
+
+    let total_supply: U256 = read_from(TOTAL_SUPPLY);
+
+    set_balance(owner, owner_balance - amount);
+    write_to(TOTAL_SUPPLY, total_supply - amount);
+}
+
+/// Makes `transfer_from` call `hook_entry_point` on the `hook` contract.
+#[no_mangle]
+pub extern "C" fn set_hook() {
+    let hook: Key = runtime::get_named_arg(HOOK);
+    let hook_entry_point: String = runtime::get_named_arg(HOOK_ENTRY_POINT);
+
+    write_to(HOOK, Some(hook));
+    write_to(HOOK_ENTRY_POINT, hook_entry_point);
+}
+
+/// Creates the dictionaries in the contract's own context and credits the
+/// initial supply to `owner`.
+#[no_mangle]
+pub extern "C" fn init() {
+    if runtime::get_key(BALANCES).is_some() {
+        runtime::revert(ApiError::User(ERROR_ALREADY_INITIALIZED));
+    }
+
+    let owner: Key = runtime::get_named_arg(OWNER);
+
+    storage::new_dictionary(BALANCES).unwrap_or_revert();
+    storage::new_dictionary(ALLOWANCES).unwrap_or_revert();
+
+    set_balance(owner, read_from(TOTAL_SUPPLY));
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let name: String = runtime::get_named_arg(NAME);
+    let symbol: String = runtime::get_named_arg(SYMBOL);
+    let decimals: u8 = runtime::get_named_arg(DECIMALS);
+    let total_supply: U256 = runtime::get_named_arg(TOTAL_SUPPLY);
+    let version: u8 = runtime::get_named_arg(VERSION);
+    let transfer_fee_bps: u64 = runtime::get_named_arg(TRANSFER_FEE_BPS);
+
+    let mut named_keys = NamedKeys::new();
+
+    named_keys.insert(NAME.to_string(), storage::new_uref(name.clone()).into());
+    named_keys.insert(SYMBOL.to_string(), storage::new_uref(symbol).into());
+    named_keys.insert(DECIMALS.to_string(), storage::new_uref(decimals).into());
+    named_keys.insert(
+        TOTAL_SUPPLY.to_string(),
+        storage::new_uref(total_supply).into(),
+    );
+    named_keys.insert(VERSION.to_string(), storage::new_uref(version).into());
+    named_keys.insert(
+        TRANSFER_FEE_BPS.to_string(),
+        storage::new_uref(transfer_fee_bps).into(),
+    );
+    named_keys.insert(HOOK.to_string(), storage::new_uref(None::<Key>).into());
+    named_keys.insert(
+        HOOK_ENTRY_POINT.to_string(),
+        storage::new_uref(String::new()).into(),
+    );
+
+    let mut entry_points: EntryPoints = EntryPoints::new();
+
+    entry_points.add_entry_point(entry_point(NAME, vec![], CLType::String));
+    entry_points.add_entry_point(entry_point(SYMBOL, vec![], CLType::String));
+    entry_points.add_entry_point(entry_point(DECIMALS, vec![], CLType::U8));
+    entry_points.add_entry_point(entry_point(TOTAL_SUPPLY, vec![], CLType::U256));
+    entry_points.add_entry_point(entry_point(
+        "balance_of",
+        vec![Parameter::new(OWNER, CLType::Key)],
+        CLType::U256,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "allowance",
+        vec![
+            Parameter::new(OWNER, CLType::Key),
+            Parameter::new(SPENDER, CLType::Key),
+        ],
+        CLType::U256,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "approve",
+        vec![
+            Parameter::new(SPENDER, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "transfer",
+        vec![
+            Parameter::new(RECIPIENT, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "transfer_from",
+        vec![
+            Parameter::new(OWNER, CLType::Key),
+            Parameter::new(RECIPIENT, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "mint",
+        vec![
+            Parameter::new(OWNER, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "burn",
+        vec![
+            Parameter::new(OWNER, CLType::Key),
+            Parameter::new(AMOUNT, CLType::U256),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "set_hook",
+        vec![
+            Parameter::new(HOOK, CLType::Key),
+            Parameter::new(HOOK_ENTRY_POINT, CLType::String),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "init",
+        vec![Parameter::new(OWNER, CLType::Key)],
+        CLType::Unit,
+    ));
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(format!("cep18_package_hash_{name}")),
+        Some(format!("cep18_access_uref_{name}")),
+    );
+
+    runtime::put_key(&format!("cep18_contract_hash_{name}"), contract_hash.into());
+
+    let owner: Key = runtime::get_caller().into();
+
+    runtime::call_contract::<()>(contract_hash, "init", runtime_args! { OWNER => owner });
+}
+
+fn entry_point(name: &str, params: Vec<Parameter>, ret: CLType) -> EntryPoint {
+    EntryPoint::new(
+        name,
+        params,
+        ret,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+/// Moves `amount` out of `sender`'s balance, of which `recipient` receives
+/// what is left after the transfer fee.
+fn move_tokens(sender: Key, recipient: Key, amount: U256) {
+    let sender_balance: U256 = balance(sender);
+
+    if sender_balance < amount {
+        runtime::revert(ApiError::User(ERROR_INSUFFICIENT_BALANCE));
+    }
+
+    let transfer_fee_bps: u64 = read_from(TRANSFER_FEE_BPS);
+    let fee: U256 = amount * U256::from(transfer_fee_bps) / U256::from(10_000);
+
+    set_balance(sender, sender_balance - amount);
+    set_balance(recipient, balance(recipient) + amount - fee);
+
+    if !fee.is_zero() {
+        let total_supply: U256 = read_from(TOTAL_SUPPLY);
+
+        write_to(TOTAL_SUPPLY, total_supply - fee);
+    }
+}
+
+fn balance(owner: Key) -> U256 {
+    storage::dictionary_get(get_uref(BALANCES), &item_key(owner))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn set_balance(owner: Key, amount: U256) {
+    storage::dictionary_put(get_uref(BALANCES), &item_key(owner), amount);
+}
+
+fn allowance_of(owner: Key, spender: Key) -> U256 {
+    storage::dictionary_get(get_uref(ALLOWANCES), &pair_key(owner, spender))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn immediate_caller() -> Key {
+    let call_stack_element = runtime::get_call_stack()
+        .into_iter()
+        .rev()
+        .nth(1)
+        .unwrap_or_revert();
+
+    match call_stack_element {
+        CallStackElement::Session { account_hash } => account_hash.into(),
+        CallStackElement::StoredSession { account_hash, .. } => account_hash.into(),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        } => contract_package_hash.into(),
+    }
+}
+
+/// Same item keys as the stake contract, so tests can read balances directly.
+fn item_key(owner: Key) -> String {
+    match owner {
+        Key::Account(account_hash) => account_hash.to_string(),
+        Key::Hash(hash_addr) => ContractHash::new(hash_addr).to_string(),
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
+
+fn pair_key(owner: Key, spender: Key) -> String {
+    let mut preimage: Vec<u8> = owner.to_bytes().unwrap_or_revert();
+    preimage.append(&mut spender.to_bytes().unwrap_or_revert());
+
+    let mut hash = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(&preimage);
+    hasher.finalize(&mut hash);
+
+    hash.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn get_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .try_into()
+        .unwrap_or_revert()
+}
+
+fn read_from<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(get_uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+fn write_to<T: CLTyped + ToBytes>(name: &str, value: T) {
+    storage::write(get_uref(name), value);
+}
+
+fn ret<T: CLTyped + ToBytes>(value: T) {
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert())
+}
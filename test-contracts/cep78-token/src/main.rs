@@ -0,0 +1,307 @@
+#![no_std]
+#![no_main]
+
+//! Minimal CEP-78 collection with ordinal token ids for the integration tests.
+//! Anyone may mint and update metadata, only the holder of a token may
+//! transfer or burn it.
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{FromBytes, ToBytes},
+    contracts::NamedKeys,
+    runtime_args,
+    system::CallStackElement,
+    ApiError, CLType, CLTyped, CLValue, ContractHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, RuntimeArgs, URef,
+};
+use core::convert::TryInto;
+
+const COLLECTION_NAME: &str = "collection_name";
+const NUMBER_OF_MINTED_TOKENS: &str = "number_of_minted_tokens";
+const TOKEN_OWNERS: &str = "token_owners";
+const METADATA: &str = "metadata";
+const BALANCES: &str = "balances";
+const TOKEN_ID: &str = "token_id";
+const TOKEN_OWNER: &str = "token_owner";
+const TOKEN_META_DATA: &str = "token_meta_data";
+const SOURCE_KEY: &str = "source_key";
+const TARGET_KEY: &str = "target_key";
+
+const ERROR_INVALID_TOKEN_ID: u16 = 1;
+const ERROR_NOT_OWNER: u16 = 2;
+const ERROR_ALREADY_INITIALIZED: u16 = 3;
+
+#[no_mangle]
+pub extern "C" fn collection_name() {
+    ret(read_from::<String>(COLLECTION_NAME));
+}
+
+#[no_mangle]
+pub extern "C" fn balance_of() {
+    let token_owner: Key = runtime::get_named_arg(TOKEN_OWNER);
+
+    ret(balance(token_owner));
+}
+
+#[no_mangle]
+pub extern "C" fn owner_of() {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+
+    ret(owner(token_id));
+}
+
+#[no_mangle]
+pub extern "C" fn metadata() {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+    let metadata: String = storage::dictionary_get(get_uref(METADATA), &token_id.to_string())
+        .unwrap_or_revert()
+        .unwrap_or_revert_with(ApiError::User(ERROR_INVALID_TOKEN_ID));
+
+    ret(metadata);
+}
+
+/// Mints the next token id to `token_owner` and returns the collection name,
+/// the owner and the token id.
+#[no_mangle]
+pub extern "C" fn mint() {
+    let token_owner: Key = runtime::get_named_arg(TOKEN_OWNER);
+    let token_meta_data: String = runtime::get_named_arg(TOKEN_META_DATA);
+    let token_id: u64 = read_from(NUMBER_OF_MINTED_TOKENS);
+
+    write_to(NUMBER_OF_MINTED_TOKENS, token_id + 1);
+    set_owner(token_id, token_owner);
+    set_metadata(token_id, token_meta_data);
+    set_balance(token_owner, balance(token_owner) + 1);
+
+    ret((
+        read_from::<String>(COLLECTION_NAME),
+        token_owner,
+        token_id.to_string(),
+    ));
+}
+
+#[no_mangle]
+pub extern "C" fn set_token_metadata() {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+    let token_meta_data: String = runtime::get_named_arg(TOKEN_META_DATA);
+
+    owner(token_id);
+    set_metadata(token_id, token_meta_data);
+}
+
+#[no_mangle]
+pub extern "C" fn transfer() {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+    let source_key: Key = runtime::get_named_arg(SOURCE_KEY);
+    let target_key: Key = runtime::get_named_arg(TARGET_KEY);
+
+    if owner(token_id) != source_key || source_key != immediate_caller() {
+        runtime::revert(ApiError::User(ERROR_NOT_OWNER));
+    }
+
+    set_owner(token_id, target_key);
+    set_balance(source_key, balance(source_key) - 1);
+    set_balance(target_key, balance(target_key) + 1);
+}
+
+#[no_mangle]
+pub extern "C" fn burn() {
+    let token_id: u64 = runtime::get_named_arg(TOKEN_ID);
+    let token_owner: Key = owner(token_id);
+
+    if token_owner != immediate_caller() {
+        runtime::revert(ApiError::User(ERROR_NOT_OWNER));
+    }
+
+    storage::dictionary_put(get_uref(TOKEN_OWNERS), &token_id.to_string(), None::<Key>);
+    set_balance(token_owner, balance(token_owner) - 1);
+}
+
+/// Creates the dictionaries in the contract's own context.
+#[no_mangle]
+pub extern "C" fn init() {
+    if runtime::get_key(TOKEN_OWNERS).is_some() {
+        runtime::revert(ApiError::User(ERROR_ALREADY_INITIALIZED));
+    }
+
+    storage::new_dictionary(TOKEN_OWNERS).unwrap_or_revert();
+    storage::new_dictionary(METADATA).unwrap_or_revert();
+    storage::new_dictionary(BALANCES).unwrap_or_revert();
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let collection_name: String = runtime::get_named_arg(COLLECTION_NAME);
+
+    let mut named_keys = NamedKeys::new();
+
+    named_keys.insert(
+        COLLECTION_NAME.to_string(),
+        storage::new_uref(collection_name.clone()).into(),
+    );
+    named_keys.insert(
+        NUMBER_OF_MINTED_TOKENS.to_string(),
+        storage::new_uref(0u64).into(),
+    );
+
+    let mut entry_points: EntryPoints = EntryPoints::new();
+
+    entry_points.add_entry_point(entry_point(COLLECTION_NAME, vec![], CLType::String));
+    entry_points.add_entry_point(entry_point(
+        "balance_of",
+        vec![Parameter::new(TOKEN_OWNER, CLType::Key)],
+        CLType::U64,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "owner_of",
+        vec![Parameter::new(TOKEN_ID, CLType::U64)],
+        CLType::Key,
+    ));
+    entry_points.add_entry_point(entry_point(
+        METADATA,
+        vec![Parameter::new(TOKEN_ID, CLType::U64)],
+        CLType::String,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "mint",
+        vec![
+            Parameter::new(TOKEN_OWNER, CLType::Key),
+            Parameter::new(TOKEN_META_DATA, CLType::String),
+        ],
+        <(String, Key, String)>::cl_type(),
+    ));
+    entry_points.add_entry_point(entry_point(
+        "set_token_metadata",
+        vec![
+            Parameter::new(TOKEN_ID, CLType::U64),
+            Parameter::new(TOKEN_META_DATA, CLType::String),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "transfer",
+        vec![
+            Parameter::new(TOKEN_ID, CLType::U64),
+            Parameter::new(SOURCE_KEY, CLType::Key),
+            Parameter::new(TARGET_KEY, CLType::Key),
+        ],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point(
+        "burn",
+        vec![Parameter::new(TOKEN_ID, CLType::U64)],
+        CLType::Unit,
+    ));
+    entry_points.add_entry_point(entry_point("init", vec![], CLType::Unit));
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(named_keys),
+        Some(format!("cep78_package_hash_{collection_name}")),
+        Some(format!("cep78_access_uref_{collection_name}")),
+    );
+
+    runtime::put_key(
+        &format!("cep78_contract_hash_{collection_name}"),
+        contract_hash.into(),
+    );
+
+    runtime::call_contract::<()>(contract_hash, "init", runtime_args! {});
+}
+
+fn entry_point(name: &str, params: Vec<Parameter>, ret: CLType) -> EntryPoint {
+    EntryPoint::new(
+        name,
+        params,
+        ret,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    )
+}
+
+fn owner(token_id: u64) -> Key {
+    storage::dictionary_get::<Option<Key>>(get_uref(TOKEN_OWNERS), &token_id.to_string())
+        .unwrap_or_revert()
+        .flatten()
+        .unwrap_or_revert_with(ApiError::User(ERROR_INVALID_TOKEN_ID))
+}
+
+fn set_owner(token_id: u64, token_owner: Key) {
+    storage::dictionary_put(
+        get_uref(TOKEN_OWNERS),
+        &token_id.to_string(),
+        Some(token_owner),
+    );
+}
+
+fn set_metadata(token_id: u64, token_meta_data: String) {
+    storage::dictionary_put(get_uref(METADATA), &token_id.to_string(), token_meta_data);
+}
+
+fn balance(token_owner: Key) -> u64 {
+    storage::dictionary_get(get_uref(BALANCES), &item_key(token_owner))
+        .unwrap_or_revert()
+        .unwrap_or_default()
+}
+
+fn set_balance(token_owner: Key, amount: u64) {
+    storage::dictionary_put(get_uref(BALANCES), &item_key(token_owner), amount);
+}
+
+fn immediate_caller() -> Key {
+    let call_stack_element = runtime::get_call_stack()
+        .into_iter()
+        .rev()
+        .nth(1)
+        .unwrap_or_revert();
+
+    match call_stack_element {
+        CallStackElement::Session { account_hash } => account_hash.into(),
+        CallStackElement::StoredSession { account_hash, .. } => account_hash.into(),
+        CallStackElement::StoredContract {
+            contract_package_hash,
+            ..
+        } => contract_package_hash.into(),
+    }
+}
+
+/// Same item keys as the stake contract, so tests can read balances directly.
+fn item_key(token_owner: Key) -> String {
+    match token_owner {
+        Key::Account(account_hash) => account_hash.to_string(),
+        Key::Hash(hash_addr) => ContractHash::new(hash_addr).to_string(),
+        _ => runtime::revert(ApiError::InvalidArgument),
+    }
+}
+
+fn get_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .try_into()
+        .unwrap_or_revert()
+}
+
+fn read_from<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(get_uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+fn write_to<T: CLTyped + ToBytes>(name: &str, value: T) {
+    storage::write(get_uref(name), value);
+}
+
+fn ret<T: CLTyped + ToBytes>(value: T) {
+    runtime::ret(CLValue::from_t(value).unwrap_or_revert())
+}
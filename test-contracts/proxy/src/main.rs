@@ -0,0 +1,69 @@
+#![no_std]
+#![no_main]
+
+//! Stored contract that forwards calls to other contracts, so tests can act as
+//! a contract caller instead of an account.
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::{
+    bytesrepr::{Bytes, FromBytes},
+    contracts::NamedKeys,
+    ApiError, CLType, CLTyped, ContractHash, EntryPoint, EntryPointAccess, EntryPointType,
+    EntryPoints, Key, Parameter, RuntimeArgs,
+};
+
+const CONTRACT: &str = "contract";
+const ENTRY_POINT: &str = "entry_point";
+const ARGS: &str = "args";
+const ENTRY_POINT_FORWARD: &str = "forward";
+
+/// Calls `entry_point` of `contract` with the serialized runtime arguments
+/// `args`. The entry point must not return a value.
+#[no_mangle]
+pub extern "C" fn forward() {
+    let contract: Key = runtime::get_named_arg(CONTRACT);
+    let entry_point: String = runtime::get_named_arg(ENTRY_POINT);
+    let args: Bytes = runtime::get_named_arg(ARGS);
+    let (args, _): (RuntimeArgs, _) = RuntimeArgs::from_bytes(&args).unwrap_or_revert();
+
+    runtime::call_contract::<()>(
+        contract
+            .into_hash()
+            .map(ContractHash::new)
+            .unwrap_or_revert_with(ApiError::InvalidArgument),
+        &entry_point,
+        args,
+    );
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points: EntryPoints = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_FORWARD,
+        vec![
+            Parameter::new(CONTRACT, CLType::Key),
+            Parameter::new(ENTRY_POINT, CLType::String),
+            Parameter::new(ARGS, Bytes::cl_type()),
+        ],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(NamedKeys::new()),
+        Some(String::from("proxy_package_hash")),
+        Some(String::from("proxy_access_uref")),
+    );
+
+    runtime::put_key("proxy_contract_hash", contract_hash.into());
+}
@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+//! Stand-in for the registry the stake contract reports its contract hash to
+//! on install. It only records the last value it was given.
+
+extern crate alloc;
+
+use alloc::{string::String, vec};
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{
+    contracts::NamedKeys, CLType, EntryPoint, EntryPointAccess, EntryPointType, EntryPoints, Key,
+    Parameter,
+};
+
+const DATA: &str = "data";
+const ENTRY_POINT_INSERT: &str = "insert";
+
+#[no_mangle]
+pub extern "C" fn insert() {
+    let data: String = runtime::get_named_arg(DATA);
+    let data: Key = storage::new_uref(data).into();
+
+    runtime::put_key(DATA, data);
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let mut entry_points: EntryPoints = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        ENTRY_POINT_INSERT,
+        vec![Parameter::new(DATA, CLType::String)],
+        CLType::Unit,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    let (contract_hash, _contract_version) = storage::new_contract(
+        entry_points,
+        Some(NamedKeys::new()),
+        Some(String::from("storage_package_hash")),
+        Some(String::from("storage_access_uref")),
+    );
+
+    runtime::put_key("storage_contract_hash", contract_hash.into());
+}
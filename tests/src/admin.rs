@@ -0,0 +1,204 @@
+use crate::utility::{
+    setup, Error, ALICE, APR, BOB, DEPOSIT_END_TIME, DEPOSIT_TIME, LOCK_PERIOD, MAX_CAP,
+    REWARD_FUNDS, UNLOCK_TIME, USER_TOKENS,
+};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_add_rewards_and_raise_the_apr() {
+    let mut context = setup();
+
+    context.approve(*DEFAULT_ACCOUNT_ADDR, REWARD_FUNDS);
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "add_rewards",
+            runtime_args! {
+                "amount" => U256::from(REWARD_FUNDS),
+                "apr" => 2 * APR,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.named_value::<U256>("total_reward"),
+        U256::from(2 * REWARD_FUNDS)
+    );
+    assert_eq!(context.named_value::<u64>("apr"), 2 * APR);
+}
+
+#[test]
+fn should_not_raise_the_apr_beyond_the_funded_rewards() {
+    let mut context = setup();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "add_rewards",
+            runtime_args! {
+                "amount" => U256::zero(),
+                "apr" => 2 * APR,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InsufficientRewardFunds);
+}
+
+#[test]
+fn should_keep_the_unlock_time_of_positions_when_extending_the_lock() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "add_rewards",
+            runtime_args! {
+                "amount" => U256::zero(),
+                "lock_period" => 2 * LOCK_PERIOD,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context.stake(BOB, 100);
+
+    context
+        .call_pool(BOB, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+    context.assert_error(Error::StillLockPeriod);
+
+    context
+        .call_pool(ALICE, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS)
+    );
+}
+
+#[test]
+fn should_update_the_stake_limits_and_max_cap() {
+    let mut context = setup();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_stake_limits",
+            runtime_args! {
+                "min_stake" => U256::from(20),
+                "max_stake" => U256::from(500),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_max_cap",
+            runtime_args! {
+                "max_cap" => U256::from(MAX_CAP / 2),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(context.named_value::<U256>("min_stake"), U256::from(20));
+    assert_eq!(context.named_value::<U256>("max_stake"), U256::from(500));
+    assert_eq!(
+        context.named_value::<U256>("max_cap"),
+        U256::from(MAX_CAP / 2)
+    );
+
+    context.approve(ALICE, 600);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(600),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+    context.assert_error(Error::AmountLimits);
+}
+
+#[test]
+fn should_not_raise_the_max_cap_beyond_the_funded_rewards() {
+    let mut context = setup();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_max_cap",
+            runtime_args! {
+                "max_cap" => U256::from(2 * MAX_CAP),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InsufficientRewardFunds);
+}
+
+#[test]
+fn should_keep_the_unlock_time_of_positions_when_extending_the_deposit_window() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "extend_deposit_window",
+            runtime_args! {
+                "deposit_end_time" => 2 * DEPOSIT_END_TIME,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context.stake(BOB, 100);
+
+    context
+        .call_pool(BOB, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+    context.assert_error(Error::StillLockPeriod);
+
+    context
+        .call_pool(ALICE, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS)
+    );
+}
+
+#[test]
+fn should_not_shorten_the_deposit_window() {
+    let mut context = setup();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "extend_deposit_window",
+            runtime_args! {
+                "deposit_end_time" => DEPOSIT_END_TIME - 1,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InvalidDepositWindow);
+}
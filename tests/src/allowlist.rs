@@ -0,0 +1,49 @@
+use crate::utility::{setup_with, Error, TestContext, ALICE, DEPOSIT_TIME};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+/// A notified pool gated by the owner-managed allowlist.
+fn setup_allowlist() -> TestContext {
+    setup_with(runtime_args! {
+        "allowlist_enabled" => true,
+    })
+}
+
+#[test]
+fn should_stake_once_allowlisted() {
+    let mut context = setup_allowlist();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "add_to_allowlist",
+            runtime_args! {
+                "addresses" => vec![Key::from(ALICE)],
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context.stake(ALICE, 100);
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(100));
+}
+
+#[test]
+fn should_not_stake_without_being_allowlisted() {
+    let mut context = setup_allowlist();
+
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::NotWhitelisted);
+}
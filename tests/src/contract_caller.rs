@@ -0,0 +1,83 @@
+use crate::utility::{setup, Error, TestContext, ALICE, DEPOSIT_TIME, UNLOCK_TIME};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, ContractHash, ContractPackageHash, Key, RuntimeArgs, U256};
+
+/// A notified pool and a proxy contract that has staked 100 tokens through it.
+fn setup_proxy_stake() -> (TestContext, ContractHash, ContractPackageHash) {
+    let mut context = setup();
+    let (proxy, proxy_package) = context.install_proxy();
+    let (token, pool, pool_key) = (context.token, context.pool, context.pool_key());
+
+    context
+        .call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "transfer",
+            runtime_args! {
+                "recipient" => Key::from(proxy_package),
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .forward(
+            ALICE,
+            proxy,
+            token,
+            "approve",
+            runtime_args! {
+                "spender" => pool_key,
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .forward(
+            ALICE,
+            proxy,
+            pool,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    (context, proxy, proxy_package)
+}
+
+#[test]
+fn should_let_a_contract_stake_and_unstake() {
+    let (mut context, proxy, proxy_package) = setup_proxy_stake();
+    let pool: ContractHash = context.pool;
+
+    assert_eq!(context.stake_of(Key::from(proxy_package)), U256::from(100));
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::zero());
+
+    context
+        .forward(ALICE, proxy, pool, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(proxy_package)),
+        U256::from(100)
+    );
+}
+
+#[test]
+fn should_not_let_the_deploying_account_unstake_for_the_contract() {
+    let (mut context, _, _) = setup_proxy_stake();
+
+    context
+        .call_pool(ALICE, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::InsufficientStakeBalance);
+}
@@ -0,0 +1,31 @@
+use crate::utility::{setup, Error, ALICE, APR, DEPOSIT_END_TIME, UNLOCK_TIME, USER_TOKENS};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_pay_principal_and_rewards_together() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "exit", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS + APR)
+    );
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::zero());
+}
+
+#[test]
+fn should_not_exit_during_the_lock() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "exit", runtime_args! {}, DEPOSIT_END_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::StillLockPeriod);
+}
@@ -0,0 +1,105 @@
+use crate::utility::{
+    assert_install_error, setup_with, Error, ALICE, APR, CAROL, DEPOSIT_TIME, MAX_STAKE,
+    UNLOCK_TIME, USER_TOKENS,
+};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+const FEE_BPS: u64 = 1_000;
+const DEPOSIT_FEE_BPS: u64 = 100;
+const WITHDRAWAL_FEE_BPS: u64 = 200;
+
+#[test]
+fn should_take_the_protocol_fee_from_claimed_rewards() {
+    let mut context = setup_with(runtime_args! {
+        "fee_bps" => FEE_BPS,
+        "fee_recipient" => Key::from(CAROL),
+    });
+    let fee: u64 = APR * FEE_BPS / 10_000;
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "claim", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR - fee)
+    );
+    assert_eq!(
+        context.token_balance(Key::from(CAROL)),
+        U256::from(USER_TOKENS + fee)
+    );
+    assert_eq!(context.named_value::<U256>("total_fees"), U256::from(fee));
+}
+
+#[test]
+fn should_not_raise_the_protocol_fee() {
+    let mut context = setup_with(runtime_args! {
+        "fee_bps" => FEE_BPS,
+    });
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_fee_bps",
+            runtime_args! {
+                "fee_bps" => 2 * FEE_BPS,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::FeeIncreaseNotAllowed);
+}
+
+#[test]
+fn should_take_the_deposit_and_withdrawal_fees_from_the_principal() {
+    let mut context = setup_with(runtime_args! {
+        "deposit_fee_bps" => DEPOSIT_FEE_BPS,
+        "withdrawal_fee_bps" => WITHDRAWAL_FEE_BPS,
+        "fee_recipient" => Key::from(CAROL),
+    });
+    let deposit_fee: u64 = MAX_STAKE * DEPOSIT_FEE_BPS / 10_000;
+    let staked: u64 = MAX_STAKE - deposit_fee;
+    let withdrawal_fee: u64 = staked * WITHDRAWAL_FEE_BPS / 10_000;
+
+    context.stake(ALICE, MAX_STAKE);
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(staked));
+    assert_eq!(
+        context.named_value::<U256>("total_deposit_fees"),
+        U256::from(deposit_fee)
+    );
+
+    context
+        .call_pool(ALICE, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - deposit_fee - withdrawal_fee)
+    );
+    assert_eq!(
+        context.token_balance(Key::from(CAROL)),
+        U256::from(USER_TOKENS + deposit_fee + withdrawal_fee)
+    );
+    assert_eq!(
+        context.named_value::<U256>("total_withdrawal_fees"),
+        U256::from(withdrawal_fee)
+    );
+    assert_eq!(context.named_value::<U256>("total_fees"), U256::zero());
+}
+
+#[test]
+fn should_not_install_with_a_deposit_fee_of_everything() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "deposit_fee_bps" => 10_000u64,
+        },
+        Error::InvalidFeeBps,
+    );
+}
@@ -0,0 +1,80 @@
+use crate::utility::{
+    assert_install_error, Error, TestContext, APR, DEPOSIT_START_TIME, MAX_CAP, MAX_STAKE,
+};
+use casper_types::{runtime_args, RuntimeArgs, U256};
+
+#[test]
+fn should_install_with_valid_parameters() {
+    let context = TestContext::install(runtime_args! {}, runtime_args! {});
+
+    assert_eq!(context.named_value::<U256>("max_cap"), U256::from(MAX_CAP));
+    assert!(!context.named_value::<bool>("notified"));
+}
+
+#[test]
+fn should_not_install_with_an_inverted_apr_range() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "fixed_apr" => 0u64,
+            "min_apr" => APR + 1,
+            "max_apr" => APR,
+        },
+        Error::InvalidAprRange,
+    );
+}
+
+#[test]
+fn should_not_install_with_a_zero_max_cap() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "max_cap" => U256::zero(),
+        },
+        Error::InvalidMaxCap,
+    );
+}
+
+#[test]
+fn should_not_install_with_an_empty_deposit_window() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "deposit_end_time" => DEPOSIT_START_TIME,
+        },
+        Error::InvalidDepositWindow,
+    );
+}
+
+#[test]
+fn should_not_install_with_a_lock_period_past_the_end_of_time() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "lock_period" => u64::MAX,
+        },
+        Error::InvalidLockPeriod,
+    );
+}
+
+#[test]
+fn should_not_install_with_a_min_stake_above_the_max_stake() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "min_stake" => U256::from(MAX_STAKE + 1),
+        },
+        Error::InvalidStakeLimits,
+    );
+}
+
+#[test]
+fn should_not_install_with_a_max_stake_above_the_max_cap() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "max_stake" => U256::from(MAX_CAP + 1),
+        },
+        Error::InvalidStakeLimits,
+    );
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+#[allow(dead_code)]
+mod utility;
+
+#[cfg(test)]
+mod admin;
+#[cfg(test)]
+mod allowlist;
+#[cfg(test)]
+mod contract_caller;
+#[cfg(test)]
+mod exit;
+#[cfg(test)]
+mod fees;
+#[cfg(test)]
+mod install;
+#[cfg(test)]
+mod interim_claims;
+#[cfg(test)]
+mod merkle;
+#[cfg(test)]
+mod operator;
+#[cfg(test)]
+mod position_nft;
+#[cfg(test)]
+mod position_transfer;
+#[cfg(test)]
+mod receipt;
+#[cfg(test)]
+mod recipient;
+#[cfg(test)]
+mod reentrancy;
+#[cfg(test)]
+mod referral;
+#[cfg(test)]
+mod stake;
+#[cfg(test)]
+mod token_version;
+#[cfg(test)]
+mod transfer_fee;
+#[cfg(test)]
+mod unbonding;
+#[cfg(test)]
+mod vesting;
+#[cfg(test)]
+mod voucher;
+
+fn main() {
+    panic!("Execute \"cargo test\" to test the contract, not \"cargo run\".");
+}
@@ -0,0 +1,37 @@
+use crate::utility::{
+    setup, setup_with, Error, ALICE, APR, DEPOSIT_END_TIME, LOCK_PERIOD, USER_TOKENS,
+};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+/// Halfway through the lock of positions opened inside the deposit window.
+const MID_LOCK_TIME: u64 = DEPOSIT_END_TIME + LOCK_PERIOD / 2;
+
+#[test]
+fn should_claim_the_accrued_rewards_during_the_lock() {
+    let mut context = setup_with(runtime_args! {
+        "interim_claims" => true,
+    });
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "claim", runtime_args! {}, MID_LOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR / 2)
+    );
+}
+
+#[test]
+fn should_not_claim_during_the_lock_without_interim_claims() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "claim", runtime_args! {}, MID_LOCK_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::StillLockPeriod);
+}
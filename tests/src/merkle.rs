@@ -0,0 +1,93 @@
+use crate::utility::{setup, sha3_256, Error, TestContext, ALICE, BOB, DEPOSIT_TIME, MAX_STAKE};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{bytesrepr::ToBytes, runtime_args, Key, RuntimeArgs, U256};
+
+/// Above `MAX_STAKE`, the proven cap replaces it.
+const ALICE_CAP: u64 = MAX_STAKE + 500;
+const BOB_CAP: u64 = 200;
+
+fn leaf(staker: Key, stake_cap: u64) -> [u8; 32] {
+    let mut preimage: Vec<u8> = vec![0x00];
+    preimage.append(&mut staker.to_bytes().unwrap());
+    preimage.append(&mut U256::from(stake_cap).to_bytes().unwrap());
+
+    sha3_256(&preimage)
+}
+
+fn node(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    let (left, right) = if left <= right {
+        (left, right)
+    } else {
+        (right, left)
+    };
+    let mut preimage: Vec<u8> = vec![0x01];
+    preimage.extend_from_slice(&left);
+    preimage.extend_from_slice(&right);
+
+    sha3_256(&preimage)
+}
+
+/// A pool gated by the tree over Alice's and Bob's caps.
+fn setup_merkle() -> TestContext {
+    let mut context = setup();
+    let merkle_root: [u8; 32] = node(
+        leaf(Key::from(ALICE), ALICE_CAP),
+        leaf(Key::from(BOB), BOB_CAP),
+    );
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_merkle_root",
+            runtime_args! {
+                "merkle_root" => merkle_root,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+}
+
+#[test]
+fn should_stake_up_to_the_proven_cap() {
+    let mut context = setup_merkle();
+
+    context.approve(ALICE, ALICE_CAP);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(ALICE_CAP),
+                "stake_cap" => U256::from(ALICE_CAP),
+                "proof" => vec![leaf(Key::from(BOB), BOB_CAP)],
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(ALICE_CAP));
+}
+
+#[test]
+fn should_not_stake_with_a_wrong_cap() {
+    let mut context = setup_merkle();
+
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+                "stake_cap" => U256::from(2 * ALICE_CAP),
+                "proof" => vec![leaf(Key::from(BOB), BOB_CAP)],
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InvalidMerkleProof);
+}
@@ -0,0 +1,124 @@
+use crate::utility::{
+    setup, Error, TestContext, ALICE, APR, BOB, CAROL, DEPOSIT_TIME, MAX_STAKE, UNLOCK_TIME,
+    USER_TOKENS,
+};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+/// A pool where Alice staked 100 and approved Bob as her operator.
+fn setup_operator() -> TestContext {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "approve_operator",
+            runtime_args! {
+                "operator" => Key::from(BOB),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+}
+
+#[test]
+fn should_let_an_operator_claim_for_the_staker() {
+    let mut context = setup_operator();
+
+    context
+        .call_pool(
+            BOB,
+            "claim",
+            runtime_args! {
+                "staker" => Key::from(ALICE),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR)
+    );
+    assert_eq!(
+        context.token_balance(Key::from(BOB)),
+        U256::from(USER_TOKENS)
+    );
+}
+
+#[test]
+fn should_not_let_anyone_else_claim_for_the_staker() {
+    let mut context = setup_operator();
+
+    context
+        .call_pool(
+            CAROL,
+            "claim",
+            runtime_args! {
+                "staker" => Key::from(ALICE),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::NotOperator);
+}
+
+#[test]
+fn should_compound_rewards_into_the_stake() {
+    let mut context = setup_operator();
+
+    context
+        .call_pool(
+            BOB,
+            "compound",
+            runtime_args! {
+                "staker" => Key::from(ALICE),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(100 + APR));
+    assert_eq!(
+        context.named_value::<U256>("total_supply"),
+        U256::from(100 + APR)
+    );
+}
+
+#[test]
+fn should_compound_on_top_of_the_remaining_liquidity() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context.stake(BOB, 100);
+    context
+        .call_pool(BOB, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+    context
+        .call_pool(ALICE, "compound", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.named_value::<U256>("liquidity"),
+        U256::from(100 + APR)
+    );
+}
+
+#[test]
+fn should_not_compound_beyond_the_max_stake() {
+    let mut context = setup();
+
+    context.stake(ALICE, MAX_STAKE);
+    context
+        .call_pool(ALICE, "compound", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::AmountLimits);
+}
@@ -0,0 +1,139 @@
+use crate::utility::{
+    install_collection, prepare, Error, TestContext, ALICE, APR, BOB, DEPOSIT_TIME, UNLOCK_TIME,
+    USER_TOKENS,
+};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+/// A notified pool minting position NFTs, where Alice staked 100 and holds
+/// token 0.
+fn setup_positions() -> (TestContext, ContractHash) {
+    let (mut builder, storage, token) = prepare(runtime_args! {});
+    let nft: ContractHash = install_collection(&mut builder, "positions");
+    let mut context = TestContext::install_pool(
+        builder,
+        storage,
+        token,
+        runtime_args! {
+            "position_nft" => Key::from(nft),
+        },
+    );
+
+    context.notify();
+    context.stake(ALICE, 100);
+
+    (context, nft)
+}
+
+/// Alice sells token 0 to Bob.
+fn sell_position(context: &mut TestContext, nft: ContractHash) {
+    context
+        .call(
+            ALICE,
+            nft,
+            "transfer",
+            runtime_args! {
+                "token_id" => 0u64,
+                "source_key" => Key::from(ALICE),
+                "target_key" => Key::from(BOB),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+}
+
+fn owner_of(context: &TestContext, nft: ContractHash, token_id: u64) -> Option<Key> {
+    context
+        .dictionary_value::<Option<Key>>(nft, "token_owners", &token_id.to_string())
+        .flatten()
+}
+
+#[test]
+fn should_pay_the_principal_to_the_token_holder() {
+    let (mut context, nft) = setup_positions();
+
+    assert_eq!(owner_of(&context, nft, 0), Some(Key::from(ALICE)));
+
+    sell_position(&mut context, nft);
+    context
+        .call_pool(
+            BOB,
+            "unstake",
+            runtime_args! {
+                "token_id" => 0u64,
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(BOB)),
+        U256::from(USER_TOKENS + 100)
+    );
+}
+
+#[test]
+fn should_not_let_the_seller_unstake() {
+    let (mut context, nft) = setup_positions();
+
+    sell_position(&mut context, nft);
+    context
+        .call_pool(
+            ALICE,
+            "unstake",
+            runtime_args! {
+                "token_id" => 0u64,
+            },
+            UNLOCK_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::NotPositionOwner);
+}
+
+#[test]
+fn should_open_a_new_position_after_selling_the_token() {
+    let (mut context, nft) = setup_positions();
+
+    sell_position(&mut context, nft);
+    context.stake(ALICE, 100);
+
+    assert_eq!(owner_of(&context, nft, 1), Some(Key::from(ALICE)));
+    assert_eq!(owner_of(&context, nft, 0), Some(Key::from(BOB)));
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(100));
+    assert_eq!(context.named_value::<U256>("total_supply"), U256::from(200));
+}
+
+#[test]
+fn should_let_an_operator_claim_for_the_token_holder() {
+    let (mut context, _) = setup_positions();
+
+    context
+        .call_pool(
+            ALICE,
+            "approve_operator",
+            runtime_args! {
+                "operator" => Key::from(BOB),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .call_pool(
+            BOB,
+            "claim",
+            runtime_args! {
+                "token_id" => 0u64,
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR)
+    );
+}
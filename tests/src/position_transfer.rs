@@ -0,0 +1,49 @@
+use crate::utility::{setup, setup_with, Error, ALICE, BOB, DEPOSIT_TIME};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_move_stake_and_rewards_proportionally() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "transfer_position",
+            runtime_args! {
+                "to" => Key::from(BOB),
+                "amount" => U256::from(40),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(60));
+    assert_eq!(context.reward_of(Key::from(ALICE)), U256::from(6));
+    assert_eq!(context.stake_of(Key::from(BOB)), U256::from(40));
+    assert_eq!(context.reward_of(Key::from(BOB)), U256::from(4));
+    assert_eq!(context.named_value::<U256>("total_supply"), U256::from(100));
+}
+
+#[test]
+fn should_not_transfer_positions_when_disabled() {
+    let mut context = setup_with(runtime_args! {
+        "transferable" => false,
+    });
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "transfer_position",
+            runtime_args! {
+                "to" => Key::from(BOB),
+                "amount" => U256::from(40),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::TransfersDisabled);
+}
@@ -0,0 +1,190 @@
+use crate::utility::{
+    install_token, prepare, Error, TestContext, ALICE, APR, BOB, CAROL, DEPOSIT_TIME, UNLOCK_TIME,
+    USER_TOKENS,
+};
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+/// A notified pool minting receipts of its own token, where Alice staked 100.
+fn setup_receipt() -> (TestContext, ContractHash) {
+    let (mut builder, storage, token) = prepare(runtime_args! {});
+    let receipt: ContractHash = install_token(
+        &mut builder,
+        "receipt",
+        runtime_args! {
+            "total_supply" => U256::zero(),
+        },
+    );
+    let mut context = TestContext::install_pool(
+        builder,
+        storage,
+        token,
+        runtime_args! {
+            "receipt_token" => Key::from(receipt),
+        },
+    );
+
+    context.notify();
+    context.stake(ALICE, 100);
+
+    (context, receipt)
+}
+
+#[test]
+fn should_let_the_receipt_holder_redeem_the_principal() {
+    let (mut context, receipt) = setup_receipt();
+    let pool_key: Key = context.pool_key();
+
+    assert_eq!(
+        context.balance_of(receipt, Key::from(ALICE)),
+        U256::from(100)
+    );
+
+    context
+        .call(
+            ALICE,
+            receipt,
+            "transfer",
+            runtime_args! {
+                "recipient" => Key::from(BOB),
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .call(
+            BOB,
+            receipt,
+            "approve",
+            runtime_args! {
+                "spender" => pool_key,
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .call_pool(
+            BOB,
+            "unstake",
+            runtime_args! {
+                "staker" => Key::from(ALICE),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(BOB)),
+        U256::from(USER_TOKENS + 100)
+    );
+    assert_eq!(context.balance_of(receipt, Key::from(BOB)), U256::zero());
+
+    // the rewards stay with the staker
+    context
+        .call_pool(ALICE, "claim", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR)
+    );
+}
+
+#[test]
+fn should_not_redeem_without_the_receipts() {
+    let (mut context, _) = setup_receipt();
+
+    context
+        .call_pool(
+            CAROL,
+            "unstake",
+            runtime_args! {
+                "staker" => Key::from(ALICE),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InsufficientBalance);
+}
+
+#[test]
+fn should_redeem_any_amount_against_any_position() {
+    let (mut context, receipt) = setup_receipt();
+    let pool_key: Key = context.pool_key();
+
+    context.stake(BOB, 100);
+    context
+        .call(
+            ALICE,
+            receipt,
+            "transfer",
+            runtime_args! {
+                "recipient" => Key::from(CAROL),
+                "amount" => U256::from(50),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    for holder in [ALICE, CAROL] {
+        context
+            .call(
+                holder,
+                receipt,
+                "approve",
+                runtime_args! {
+                    "spender" => pool_key,
+                    "amount" => U256::from(50),
+                },
+                DEPOSIT_TIME,
+            )
+            .expect_success()
+            .commit();
+    }
+
+    // Carol's receipts come from Alice's deposit but redeem Bob's position
+    context
+        .call_pool(
+            CAROL,
+            "unstake",
+            runtime_args! {
+                "staker" => Key::from(BOB),
+                "amount" => U256::from(50),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+        .call_pool(
+            ALICE,
+            "unstake",
+            runtime_args! {
+                "amount" => U256::from(50),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(CAROL)),
+        U256::from(USER_TOKENS + 50)
+    );
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 50)
+    );
+    assert_eq!(context.balance_of(receipt, Key::from(ALICE)), U256::zero());
+    assert_eq!(context.balance_of(receipt, Key::from(CAROL)), U256::zero());
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(50));
+    assert_eq!(context.stake_of(Key::from(BOB)), U256::from(50));
+    assert_eq!(context.named_value::<U256>("liquidity"), U256::from(100));
+}
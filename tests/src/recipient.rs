@@ -0,0 +1,48 @@
+use crate::utility::{setup, Error, ALICE, CAROL, UNLOCK_TIME, USER_TOKENS};
+use casper_types::{runtime_args, AccessRights, Key, RuntimeArgs, URef, U256};
+
+#[test]
+fn should_unstake_to_another_recipient() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "unstake",
+            runtime_args! {
+                "recipient" => Key::from(CAROL),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(CAROL)),
+        U256::from(USER_TOKENS + 100)
+    );
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100)
+    );
+}
+
+#[test]
+fn should_not_unstake_to_a_uref() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "unstake",
+            runtime_args! {
+                "recipient" => Key::from(URef::new([4u8; 32], AccessRights::READ_ADD_WRITE)),
+            },
+            UNLOCK_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InvalidKey);
+}
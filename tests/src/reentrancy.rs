@@ -0,0 +1,47 @@
+use crate::utility::{setup, Error, ALICE, DEPOSIT_TIME};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, ContractHash, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_release_the_lock_after_each_call() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+
+    assert!(!context.named_value::<bool>("reentrancy_lock"));
+}
+
+#[test]
+fn should_reject_a_token_calling_back_into_the_pool() {
+    let mut context = setup();
+    let (token, pool): (ContractHash, ContractHash) = (context.token, context.pool);
+
+    // the token calls `claim` on the pool while `stake` pulls the deposit
+    context
+        .call(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "set_hook",
+            runtime_args! {
+                "hook" => Key::from(pool),
+                "hook_entry_point" => "claim".to_string(),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::ReentrantCall);
+}
@@ -0,0 +1,97 @@
+use crate::utility::{
+    assert_install_error, item_key, setup_with, Error, TestContext, ALICE, APR, CAROL,
+    DEPOSIT_TIME, UNLOCK_TIME, USER_TOKENS,
+};
+use casper_engine_test_support::InMemoryWasmTestBuilder;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+const REFERRAL_BPS: u64 = 1_000;
+
+/// A pool paying referrers a tenth of their referees' rewards.
+fn setup_referral() -> TestContext {
+    setup_with(runtime_args! {
+        "referral_bps" => REFERRAL_BPS,
+    })
+}
+
+/// Alice stakes 100 naming `referrer`.
+fn stake_referred(context: &mut TestContext, referrer: Key) -> &mut InMemoryWasmTestBuilder {
+    context.approve(ALICE, 100);
+    context.call_pool(
+        ALICE,
+        "stake",
+        runtime_args! {
+            "amount" => U256::from(100),
+            "referrer" => referrer,
+        },
+        DEPOSIT_TIME,
+    )
+}
+
+#[test]
+fn should_pay_the_referrer_a_share_of_the_reward() {
+    let mut context = setup_referral();
+
+    stake_referred(&mut context, Key::from(CAROL))
+        .expect_success()
+        .commit();
+
+    let referral_reward: Option<U256> = context.dictionary_value(
+        context.pool,
+        "referral_rewards_dict",
+        &item_key(Key::from(CAROL)),
+    );
+
+    assert_eq!(
+        referral_reward,
+        Some(U256::from(APR * REFERRAL_BPS / 10_000))
+    );
+
+    context
+        .call_pool(
+            CAROL,
+            "claim_referral_rewards",
+            runtime_args! {},
+            UNLOCK_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(CAROL)),
+        U256::from(USER_TOKENS + APR * REFERRAL_BPS / 10_000)
+    );
+}
+
+#[test]
+fn should_not_refer_oneself() {
+    let mut context = setup_referral();
+
+    stake_referred(&mut context, Key::from(ALICE)).expect_failure();
+
+    context.assert_error(Error::InvalidReferrer);
+}
+
+#[test]
+fn should_not_install_with_referrals_and_vesting() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "referral_bps" => REFERRAL_BPS,
+            "vesting_period" => UNLOCK_TIME,
+        },
+        Error::ConflictingRewardSchedules,
+    );
+}
+
+#[test]
+fn should_not_install_with_referrals_and_interim_claims() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "referral_bps" => REFERRAL_BPS,
+            "interim_claims" => true,
+        },
+        Error::ConflictingRewardSchedules,
+    );
+}
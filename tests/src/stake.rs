@@ -0,0 +1,117 @@
+use crate::utility::{setup, Error, ALICE, APR, BOB, DEPOSIT_TIME, MAX_STAKE, USER_TOKENS};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_stake_and_accrue_the_reward() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(100));
+    assert_eq!(context.reward_of(Key::from(ALICE)), U256::from(APR));
+    assert_eq!(context.named_value::<U256>("total_supply"), U256::from(100));
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100)
+    );
+}
+
+#[test]
+fn should_revert_with_overflow_instead_of_wrapping() {
+    let mut context = setup();
+
+    // the window end plus this lock period no longer fits a timestamp
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "add_rewards",
+            runtime_args! {
+                "amount" => U256::zero(),
+                "lock_period" => u64::MAX,
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::Overflow);
+}
+
+#[test]
+fn should_not_stake_beyond_the_allowance() {
+    let mut context = setup();
+
+    context.approve(ALICE, 50);
+    context
+        .call_pool(
+            ALICE,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::InsufficientAllowance);
+}
+
+#[test]
+fn should_stake_on_behalf_of_another_account() {
+    let mut context = setup();
+
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake_for",
+            runtime_args! {
+                "beneficiary" => Key::from(BOB),
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(context.stake_of(Key::from(BOB)), U256::from(100));
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::zero());
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100)
+    );
+}
+
+#[test]
+fn should_apply_the_beneficiary_max_stake() {
+    let mut context = setup();
+
+    context.stake(BOB, MAX_STAKE);
+    context.approve(ALICE, 100);
+    context
+        .call_pool(
+            ALICE,
+            "stake_for",
+            runtime_args! {
+                "beneficiary" => Key::from(BOB),
+                "amount" => U256::from(100),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::AmountLimits);
+}
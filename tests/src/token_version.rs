@@ -0,0 +1,34 @@
+use crate::utility::{assert_install_error, Error, TestContext, ALICE, USER_TOKENS};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+#[test]
+fn should_stake_a_token_taking_the_original_balance_of_argument() {
+    let mut context = TestContext::install(
+        runtime_args! {
+            "version" => 1u8,
+        },
+        runtime_args! {
+            "token_version" => 1u8,
+        },
+    );
+
+    context.notify();
+    context.stake(ALICE, 100);
+
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(100));
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100)
+    );
+}
+
+#[test]
+fn should_not_install_with_an_unknown_token_version() {
+    assert_install_error(
+        runtime_args! {},
+        runtime_args! {
+            "token_version" => 3u8,
+        },
+        Error::InvalidTokenVersion,
+    );
+}
@@ -0,0 +1,47 @@
+use crate::utility::{Error, TestContext, ALICE, DEPOSIT_START_TIME, REWARD_FUNDS, TOKEN_SUPPLY};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+/// A pool over a token burning 1% of every transfer.
+fn install(pool_args: RuntimeArgs) -> TestContext {
+    TestContext::install(
+        runtime_args! {
+            "transfer_fee_bps" => 100u64,
+        },
+        pool_args,
+    )
+}
+
+#[test]
+fn should_credit_only_what_arrived() {
+    let mut context = install(runtime_args! {});
+
+    context.notify();
+    context.stake(ALICE, 100);
+
+    assert_eq!(
+        context.named_value::<U256>("total_reward"),
+        U256::from(REWARD_FUNDS * 99 / 100)
+    );
+    assert_eq!(context.stake_of(Key::from(ALICE)), U256::from(99));
+    assert_eq!(context.named_value::<U256>("total_supply"), U256::from(99));
+}
+
+#[test]
+fn should_reject_a_short_transfer_when_exact_transfers_are_required() {
+    let mut context = install(runtime_args! {
+        "require_exact_transfer" => true,
+    });
+
+    context.approve(*DEFAULT_ACCOUNT_ADDR, TOKEN_SUPPLY);
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "notify",
+            runtime_args! {},
+            DEPOSIT_START_TIME,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::TransferAmountMismatch);
+}
@@ -0,0 +1,72 @@
+use crate::utility::{setup, setup_with, Error, TestContext, ALICE, UNLOCK_TIME, USER_TOKENS};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+const UNBONDING_PERIOD: u64 = 10_000;
+
+/// A pool with an unbonding period, where Alice staked 100.
+fn setup_unbonding() -> TestContext {
+    let mut context = setup_with(runtime_args! {
+        "unbonding_period" => UNBONDING_PERIOD,
+    });
+
+    context.stake(ALICE, 100);
+    context
+}
+
+#[test]
+fn should_withdraw_once_unbonded() {
+    let mut context = setup_unbonding();
+
+    context
+        .call_pool(ALICE, "request_unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_success()
+        .commit();
+
+    context
+        .call_pool(
+            ALICE,
+            "withdraw",
+            runtime_args! {},
+            UNLOCK_TIME + UNBONDING_PERIOD / 2,
+        )
+        .expect_failure();
+    context.assert_error(Error::StillUnbonding);
+
+    context
+        .call_pool(
+            ALICE,
+            "withdraw",
+            runtime_args! {},
+            UNLOCK_TIME + UNBONDING_PERIOD,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS)
+    );
+}
+
+#[test]
+fn should_not_unstake_directly_while_unbonding_is_required() {
+    let mut context = setup_unbonding();
+
+    context
+        .call_pool(ALICE, "unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::UnbondingRequired);
+}
+
+#[test]
+fn should_not_request_unstake_without_an_unbonding_period() {
+    let mut context = setup();
+
+    context.stake(ALICE, 100);
+    context
+        .call_pool(ALICE, "request_unstake", runtime_args! {}, UNLOCK_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::UnbondingDisabled);
+}
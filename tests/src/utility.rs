@@ -0,0 +1,547 @@
+//! Fixtures shared by the integration tests: a chain with funded user accounts,
+//! the storage registry and the staked token, and a notified pool on top.
+
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    DEFAULT_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{
+    engine_state::{Error as EngineStateError, ExecuteRequest},
+    execution::Error as ExecError,
+};
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{Bytes, FromBytes, ToBytes},
+    runtime_args,
+    system::mint,
+    ApiError, CLTyped, ContractHash, ContractPackageHash, Key, RuntimeArgs, U256, U512,
+};
+use tiny_keccak::{Hasher, Sha3};
+
+pub const STAKE_WASM: &str = "stake.wasm";
+pub const STORAGE_WASM: &str = "storage.wasm";
+pub const CEP18_TOKEN_WASM: &str = "cep18_token.wasm";
+pub const CEP78_TOKEN_WASM: &str = "cep78_token.wasm";
+pub const PROXY_WASM: &str = "proxy.wasm";
+
+pub const ALICE: AccountHash = AccountHash::new([1u8; 32]);
+pub const BOB: AccountHash = AccountHash::new([2u8; 32]);
+pub const CAROL: AccountHash = AccountHash::new([3u8; 32]);
+pub const USERS: [AccountHash; 3] = [ALICE, BOB, CAROL];
+
+pub const TOKEN_NAME: &str = "token";
+pub const TOKEN_SUPPLY: u64 = 1_000_000;
+/// Staked tokens every user starts with.
+pub const USER_TOKENS: u64 = 10_000;
+const USER_MOTES: u64 = 1_000_000_000_000_000;
+
+pub const MAX_CAP: u64 = 10_000;
+pub const MIN_STAKE: u64 = 10;
+pub const MAX_STAKE: u64 = 1_000;
+pub const APR: u64 = 10;
+/// What `notify` pulls into a pool with the default parameters.
+pub const REWARD_FUNDS: u64 = MAX_CAP * APR / 100;
+
+pub const DEPOSIT_START_TIME: u64 = 1_000;
+pub const DEPOSIT_END_TIME: u64 = 50_000;
+pub const LOCK_PERIOD: u64 = 100_000;
+/// A block time inside the deposit window.
+pub const DEPOSIT_TIME: u64 = 10_000;
+/// When positions opened inside the deposit window unlock.
+pub const UNLOCK_TIME: u64 = DEPOSIT_END_TIME + LOCK_PERIOD;
+
+/// Mirror of the stake contract's user errors.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum Error {
+    FatalError = 0,
+    AdminError = 1,
+    AlreadyNotified = 2,
+    InsufficientBalance = 3,
+    WaitingNotify = 4,
+    StakeIsNotStarted = 5,
+    StakeIsCompleted = 6,
+    AmountIsZero = 7,
+    AmountLimits = 8,
+    MaxCapacityError = 9,
+    InvalidKey = 10,
+    StillLockPeriod = 11,
+    StakeAmountIsZero = 12,
+    InsufficientStakeBalance = 13,
+    RefundTimeError = 14,
+    InsufficientRewardFunds = 15,
+    InvalidApr = 16,
+    InvalidLockPeriod = 17,
+    RewardPeriodEnded = 18,
+    RewardIsZero = 19,
+    InvalidMaxCap = 20,
+    InvalidStakeLimits = 21,
+    InvalidDepositWindow = 22,
+    InvalidAprRange = 23,
+    Overflow = 24,
+    Underflow = 25,
+    ReentrantCall = 26,
+    TransferAmountMismatch = 27,
+    InsufficientAllowance = 28,
+    InvalidTokenVersion = 29,
+    NotOperator = 30,
+    TransfersDisabled = 31,
+    SelfTransfer = 32,
+    PositionIsTokenized = 33,
+    ConflictingPositionTokens = 34,
+    NotPositionOwner = 35,
+    InvalidVestingSchedule = 36,
+    ConflictingRewardSchedules = 37,
+    UnbondingRequired = 38,
+    NoPendingUnstake = 39,
+    StillUnbonding = 40,
+    NotWhitelisted = 41,
+    InvalidMerkleProof = 42,
+    InvalidVoucher = 43,
+    VoucherExpired = 44,
+    VoucherAlreadyUsed = 45,
+    InvalidReferrer = 46,
+    InvalidReferralBps = 47,
+    InvalidFeeBps = 48,
+    FeeIncreaseNotAllowed = 49,
+    UnbondingDisabled = 50,
+}
+
+pub struct TestContext {
+    pub builder: InMemoryWasmTestBuilder,
+    pub storage: ContractHash,
+    pub token: ContractHash,
+    pub pool: ContractHash,
+    pub pool_package: ContractPackageHash,
+}
+
+/// A notified pool with the default parameters.
+pub fn setup() -> TestContext {
+    setup_with(runtime_args! {})
+}
+
+/// A notified pool, `pool_args` override the default install arguments.
+pub fn setup_with(pool_args: RuntimeArgs) -> TestContext {
+    let mut context = TestContext::install(runtime_args! {}, pool_args);
+
+    context.notify();
+    context
+}
+
+/// Runs genesis, funds the users and installs the storage registry and the
+/// staked token, `token_args` override the token's default install arguments.
+/// Every user is sent `USER_TOKENS` of the token.
+pub fn prepare(token_args: RuntimeArgs) -> (InMemoryWasmTestBuilder, ContractHash, ContractHash) {
+    let mut builder = InMemoryWasmTestBuilder::default();
+
+    builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+
+    for user in USERS {
+        let request = ExecuteRequestBuilder::transfer(
+            *DEFAULT_ACCOUNT_ADDR,
+            runtime_args! {
+                mint::ARG_TARGET => user,
+                mint::ARG_AMOUNT => U512::from(USER_MOTES),
+                mint::ARG_ID => <Option<u64>>::None,
+            },
+        )
+        .build();
+
+        builder.exec(request).expect_success().commit();
+    }
+
+    let request =
+        ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, STORAGE_WASM, runtime_args! {})
+            .build();
+
+    builder.exec(request).expect_success().commit();
+
+    let storage: ContractHash = contract_hash(&builder, "storage_contract_hash");
+    let token: ContractHash = install_token(&mut builder, TOKEN_NAME, token_args);
+
+    for user in USERS {
+        let request = ExecuteRequestBuilder::contract_call_by_hash(
+            *DEFAULT_ACCOUNT_ADDR,
+            token,
+            "transfer",
+            runtime_args! {
+                "recipient" => Key::from(user),
+                "amount" => U256::from(USER_TOKENS),
+            },
+        )
+        .build();
+
+        builder.exec(request).expect_success().commit();
+    }
+
+    (builder, storage, token)
+}
+
+/// Installs a test CEP-18 token named `name` whose whole supply belongs to the
+/// default account.
+pub fn install_token(
+    builder: &mut InMemoryWasmTestBuilder,
+    name: &str,
+    token_args: RuntimeArgs,
+) -> ContractHash {
+    let defaults = runtime_args! {
+        "name" => name.to_string(),
+        "symbol" => name.to_uppercase(),
+        "decimals" => 9u8,
+        "total_supply" => U256::from(TOKEN_SUPPLY),
+        "version" => 2u8,
+        "transfer_fee_bps" => 0u64,
+    };
+    let request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CEP18_TOKEN_WASM,
+        with_defaults(token_args, defaults),
+    )
+    .build();
+
+    builder.exec(request).expect_success().commit();
+
+    contract_hash(builder, &format!("cep18_contract_hash_{name}"))
+}
+
+/// Installs a test CEP-78 collection named `name`.
+pub fn install_collection(builder: &mut InMemoryWasmTestBuilder, name: &str) -> ContractHash {
+    let request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CEP78_TOKEN_WASM,
+        runtime_args! {
+            "collection_name" => name.to_string(),
+        },
+    )
+    .build();
+
+    builder.exec(request).expect_success().commit();
+
+    contract_hash(builder, &format!("cep78_contract_hash_{name}"))
+}
+
+/// Install deploy of a pool staking `token`, `pool_args` override the default
+/// install arguments.
+pub fn pool_install_request(
+    storage: ContractHash,
+    token: ContractHash,
+    pool_args: RuntimeArgs,
+) -> ExecuteRequest {
+    let defaults = runtime_args! {
+        "token" => Key::from(token),
+        "token_version" => 2u8,
+        "max_cap" => U256::from(MAX_CAP),
+        "min_stake" => U256::from(MIN_STAKE),
+        "max_stake" => U256::from(MAX_STAKE),
+        "fixed_apr" => APR,
+        "min_apr" => 0u64,
+        "max_apr" => APR,
+        "lock_period" => LOCK_PERIOD,
+        "deposit_start_time" => DEPOSIT_START_TIME,
+        "deposit_end_time" => DEPOSIT_END_TIME,
+        "storage_key" => storage,
+    };
+
+    ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        STAKE_WASM,
+        with_defaults(pool_args, defaults),
+    )
+    .build()
+}
+
+impl TestContext {
+    /// Installs the pool without notifying it.
+    pub fn install(token_args: RuntimeArgs, pool_args: RuntimeArgs) -> Self {
+        let (builder, storage, token) = prepare(token_args);
+
+        Self::install_pool(builder, storage, token, pool_args)
+    }
+
+    /// Installs the pool on a chain set up by `prepare`, without notifying it.
+    pub fn install_pool(
+        mut builder: InMemoryWasmTestBuilder,
+        storage: ContractHash,
+        token: ContractHash,
+        pool_args: RuntimeArgs,
+    ) -> Self {
+        builder
+            .exec(pool_install_request(storage, token, pool_args))
+            .expect_success()
+            .commit();
+
+        let pool: ContractHash = contract_hash(&builder, "stake_contract_hash_");
+        let pool_package = ContractPackageHash::new(
+            account_named_key(&builder, "stake_package_hash_")
+                .into_hash()
+                .expect("package key should be a hash"),
+        );
+
+        TestContext {
+            builder,
+            storage,
+            token,
+            pool,
+            pool_package,
+        }
+    }
+
+    /// The key the pool holds tokens and allowances under.
+    pub fn pool_key(&self) -> Key {
+        Key::from(self.pool_package)
+    }
+
+    /// Executes `entry_point` of `contract` as `sender` at `block_time`, the
+    /// outcome is left to the caller to check.
+    pub fn call(
+        &mut self,
+        sender: AccountHash,
+        contract: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        block_time: u64,
+    ) -> &mut InMemoryWasmTestBuilder {
+        let request =
+            ExecuteRequestBuilder::contract_call_by_hash(sender, contract, entry_point, args)
+                .with_block_time(block_time)
+                .build();
+
+        self.builder.exec(request)
+    }
+
+    pub fn call_pool(
+        &mut self,
+        sender: AccountHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        block_time: u64,
+    ) -> &mut InMemoryWasmTestBuilder {
+        let pool: ContractHash = self.pool;
+
+        self.call(sender, pool, entry_point, args, block_time)
+    }
+
+    /// Installs the call forwarding contract and returns its contract and
+    /// package hashes. Callees see the package as the caller.
+    pub fn install_proxy(&mut self) -> (ContractHash, ContractPackageHash) {
+        let request =
+            ExecuteRequestBuilder::standard(*DEFAULT_ACCOUNT_ADDR, PROXY_WASM, runtime_args! {})
+                .build();
+
+        self.builder.exec(request).expect_success().commit();
+
+        let proxy: ContractHash = contract_hash(&self.builder, "proxy_contract_hash");
+        let proxy_package = ContractPackageHash::new(
+            account_named_key(&self.builder, "proxy_package_hash")
+                .into_hash()
+                .expect("package key should be a hash"),
+        );
+
+        (proxy, proxy_package)
+    }
+
+    /// Has `proxy` call `entry_point` of `contract`, `sender` only pays for the
+    /// deploy.
+    pub fn forward(
+        &mut self,
+        sender: AccountHash,
+        proxy: ContractHash,
+        contract: ContractHash,
+        entry_point: &str,
+        args: RuntimeArgs,
+        block_time: u64,
+    ) -> &mut InMemoryWasmTestBuilder {
+        let args: Bytes = Bytes::from(args.to_bytes().expect("args should serialize"));
+
+        self.call(
+            sender,
+            proxy,
+            "forward",
+            runtime_args! {
+                "contract" => Key::from(contract),
+                "entry_point" => entry_point.to_string(),
+                "args" => args,
+            },
+            block_time,
+        )
+    }
+
+    /// Lets the pool pull `amount` of the staked token from `owner`.
+    pub fn approve(&mut self, owner: AccountHash, amount: u64) {
+        let (token, spender): (ContractHash, Key) = (self.token, self.pool_key());
+
+        self.call(
+            owner,
+            token,
+            "approve",
+            runtime_args! {
+                "spender" => spender,
+                "amount" => U256::from(amount),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    }
+
+    pub fn notify(&mut self) {
+        self.approve(*DEFAULT_ACCOUNT_ADDR, TOKEN_SUPPLY);
+        self.call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "notify",
+            runtime_args! {},
+            DEPOSIT_START_TIME,
+        )
+        .expect_success()
+        .commit();
+    }
+
+    /// Approves and stakes `amount` for `staker` inside the deposit window.
+    pub fn stake(&mut self, staker: AccountHash, amount: u64) {
+        self.approve(staker, amount);
+        self.call_pool(
+            staker,
+            "stake",
+            runtime_args! {
+                "amount" => U256::from(amount),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    }
+
+    /// Value of the pool's named key `name`.
+    pub fn named_value<T: CLTyped + FromBytes>(&self, name: &str) -> T {
+        self.builder
+            .query(None, Key::from(self.pool), &[name.to_string()])
+            .expect("named key should exist")
+            .as_cl_value()
+            .expect("named key should hold a value")
+            .clone()
+            .into_t()
+            .expect("named key should hold the expected type")
+    }
+
+    /// Item `item_key` of the dictionary `dictionary_name` of `contract`.
+    pub fn dictionary_value<T: CLTyped + FromBytes>(
+        &self,
+        contract: ContractHash,
+        dictionary_name: &str,
+        item_key: &str,
+    ) -> Option<T> {
+        let seed_uref = *self
+            .builder
+            .get_contract(contract)
+            .expect("contract should exist")
+            .named_keys()
+            .get(dictionary_name)
+            .expect("dictionary should exist")
+            .as_uref()
+            .expect("dictionary should be a uref");
+
+        self.builder
+            .query_dictionary_item(None, seed_uref, item_key)
+            .ok()
+            .map(|value| {
+                value
+                    .as_cl_value()
+                    .expect("item should hold a value")
+                    .clone()
+                    .into_t()
+                    .expect("item should hold the expected type")
+            })
+    }
+
+    pub fn stake_of(&self, staker: Key) -> U256 {
+        self.dictionary_value(self.pool, "stakes_dict", &item_key(staker))
+            .unwrap_or_default()
+    }
+
+    pub fn reward_of(&self, staker: Key) -> U256 {
+        self.dictionary_value(self.pool, "rewards_dict", &item_key(staker))
+            .unwrap_or_default()
+    }
+
+    /// Balance of `owner` in the test CEP-18 token `token`.
+    pub fn balance_of(&self, token: ContractHash, owner: Key) -> U256 {
+        self.dictionary_value(token, "balances", &item_key(owner))
+            .unwrap_or_default()
+    }
+
+    /// Balance of `owner` in the staked token.
+    pub fn token_balance(&self, owner: Key) -> U256 {
+        self.balance_of(self.token, owner)
+    }
+
+    /// Asserts the last deploy reverted with `error`.
+    pub fn assert_error(&self, error: Error) {
+        assert_expected_error(&self.builder, error);
+    }
+}
+
+/// Installs a pool with `pool_args` over a token installed with `token_args`
+/// and asserts the pool install reverted with `error`.
+pub fn assert_install_error(token_args: RuntimeArgs, pool_args: RuntimeArgs, error: Error) {
+    let (mut builder, storage, token) = prepare(token_args);
+
+    builder
+        .exec(pool_install_request(storage, token, pool_args))
+        .expect_failure();
+
+    assert_expected_error(&builder, error);
+}
+
+pub fn assert_expected_error(builder: &InMemoryWasmTestBuilder, error: Error) {
+    let actual: EngineStateError = builder.get_error().expect("deploy should have failed");
+    let expected = EngineStateError::Exec(ExecError::Revert(ApiError::User(error as u16)));
+
+    assert_eq!(format!("{actual:?}"), format!("{expected:?}"));
+}
+
+pub fn sha3_256(data: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut hasher = Sha3::v256();
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Dictionary item key the contracts use for `key`.
+pub fn item_key(key: Key) -> String {
+    match key {
+        Key::Account(account_hash) => account_hash.to_string(),
+        Key::Hash(hash_addr) => ContractHash::new(hash_addr).to_string(),
+        _ => panic!("only accounts and contracts have item keys"),
+    }
+}
+
+pub fn account_named_key(builder: &InMemoryWasmTestBuilder, name: &str) -> Key {
+    *builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(name)
+        .expect("named key should exist")
+}
+
+pub fn contract_hash(builder: &InMemoryWasmTestBuilder, name: &str) -> ContractHash {
+    ContractHash::new(
+        account_named_key(builder, name)
+            .into_hash()
+            .expect("contract key should be a hash"),
+    )
+}
+
+/// `overrides` plus every argument of `defaults` it does not set itself.
+fn with_defaults(overrides: RuntimeArgs, defaults: RuntimeArgs) -> RuntimeArgs {
+    let mut args: RuntimeArgs = overrides;
+
+    for named_arg in defaults.named_args() {
+        if args.get(named_arg.name()).is_none() {
+            args.insert_cl_value(named_arg.name(), named_arg.cl_value().clone());
+        }
+    }
+
+    args
+}
@@ -0,0 +1,67 @@
+use crate::utility::{setup_with, Error, TestContext, ALICE, APR, UNLOCK_TIME, USER_TOKENS};
+use casper_types::{runtime_args, Key, RuntimeArgs, U256};
+
+const VESTING_PERIOD: u64 = 10_000;
+const VESTING_CLIFF: u64 = 2_000;
+
+/// A pool vesting rewards after the lock, where Alice staked 100.
+fn setup_vesting() -> TestContext {
+    let mut context = setup_with(runtime_args! {
+        "vesting_period" => VESTING_PERIOD,
+        "vesting_cliff" => VESTING_CLIFF,
+    });
+
+    context.stake(ALICE, 100);
+    context
+}
+
+#[test]
+fn should_release_rewards_linearly_after_the_lock() {
+    let mut context = setup_vesting();
+
+    context
+        .call_pool(
+            ALICE,
+            "claim",
+            runtime_args! {},
+            UNLOCK_TIME + VESTING_PERIOD / 2,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR / 2)
+    );
+
+    context
+        .call_pool(
+            ALICE,
+            "claim",
+            runtime_args! {},
+            UNLOCK_TIME + VESTING_PERIOD,
+        )
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.token_balance(Key::from(ALICE)),
+        U256::from(USER_TOKENS - 100 + APR)
+    );
+}
+
+#[test]
+fn should_release_nothing_before_the_cliff() {
+    let mut context = setup_vesting();
+
+    context
+        .call_pool(
+            ALICE,
+            "claim",
+            runtime_args! {},
+            UNLOCK_TIME + VESTING_CLIFF - 1,
+        )
+        .expect_failure();
+
+    context.assert_error(Error::RewardIsZero);
+}
@@ -0,0 +1,92 @@
+use crate::utility::{setup, sha3_256, Error, TestContext, ALICE, APR, BOB, DEPOSIT_TIME};
+use casper_engine_test_support::DEFAULT_ACCOUNT_ADDR;
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    crypto, runtime_args, Key, PublicKey, RuntimeArgs, SecretKey, Signature, U256,
+};
+
+const BOOST_BPS: u64 = 500;
+const EXPIRY: u64 = DEPOSIT_TIME + 1;
+const NONCE: u64 = 7;
+
+fn signer() -> (SecretKey, PublicKey) {
+    let secret_key: SecretKey = SecretKey::ed25519_from_bytes([9u8; 32]).unwrap();
+    let public_key: PublicKey = PublicKey::from(&secret_key);
+
+    (secret_key, public_key)
+}
+
+/// A pool accepting vouchers from `signer`.
+fn setup_voucher() -> TestContext {
+    let mut context = setup();
+    let (_, public_key) = signer();
+
+    context
+        .call_pool(
+            *DEFAULT_ACCOUNT_ADDR,
+            "set_voucher_signer",
+            runtime_args! {
+                "voucher_signer" => Some(public_key),
+            },
+            DEPOSIT_TIME,
+        )
+        .expect_success()
+        .commit();
+    context
+}
+
+/// Arguments of a deposit of 100 redeeming a voucher issued to `staker`.
+fn voucher_args(context: &TestContext, staker: Key) -> RuntimeArgs {
+    let (secret_key, public_key) = signer();
+    let mut preimage: Vec<u8> = context.pool_key().to_bytes().unwrap();
+    preimage.append(&mut staker.to_bytes().unwrap());
+    preimage.append(&mut BOOST_BPS.to_bytes().unwrap());
+    preimage.append(&mut EXPIRY.to_bytes().unwrap());
+    preimage.append(&mut NONCE.to_bytes().unwrap());
+
+    let signature: Signature = crypto::sign(sha3_256(&preimage), &secret_key, &public_key);
+
+    runtime_args! {
+        "amount" => U256::from(100),
+        "voucher_signature" => Bytes::from(signature.to_bytes().unwrap()),
+        "voucher_boost_bps" => BOOST_BPS,
+        "voucher_expiry" => EXPIRY,
+        "voucher_nonce" => NONCE,
+    }
+}
+
+#[test]
+fn should_boost_the_reward_once() {
+    let mut context = setup_voucher();
+    let args: RuntimeArgs = voucher_args(&context, Key::from(ALICE));
+
+    context.approve(ALICE, 200);
+    context
+        .call_pool(ALICE, "stake", args.clone(), DEPOSIT_TIME)
+        .expect_success()
+        .commit();
+
+    assert_eq!(
+        context.reward_of(Key::from(ALICE)),
+        U256::from(APR + 100 * BOOST_BPS / 10_000)
+    );
+
+    context
+        .call_pool(ALICE, "stake", args, DEPOSIT_TIME)
+        .expect_failure();
+    context.assert_error(Error::VoucherAlreadyUsed);
+}
+
+#[test]
+fn should_not_redeem_a_voucher_for_someone_else() {
+    let mut context = setup_voucher();
+    let mut args: RuntimeArgs = voucher_args(&context, Key::from(ALICE));
+
+    args.insert("beneficiary", Key::from(ALICE)).unwrap();
+    context.approve(BOB, 100);
+    context
+        .call_pool(BOB, "stake_for", args, DEPOSIT_TIME)
+        .expect_failure();
+
+    context.assert_error(Error::InvalidVoucher);
+}